@@ -8,8 +8,9 @@ use jvmti::native::jvmti_native::{jsize, JNI_GetCreatedJavaVMs, JavaVM};
 use libloading::{Library, Symbol};
 use log::{error, info, LevelFilter};
 use simplelog::{Config, WriteLogger};
+use std::ffi::{c_char, CStr, CString};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
 use std::net::TcpListener;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -21,6 +22,9 @@ use std::{path, thread};
 static CLIENT_LIBRARY: OnceLock<Mutex<Option<Library>>> = OnceLock::new();
 static RUNNING: AtomicBool = AtomicBool::new(true);
 
+// Optional argument of the reload command used to pass a mapping file to the client
+const MAPPINGS_ARGUMENT: &str = " --mappings ";
+
 // Function called when the agent is loaded
 #[no_mangle]
 #[ctor]
@@ -88,7 +92,10 @@ fn agent_onunload() {
 }
 
 // Function to load the client library
-fn load_client_library(lib_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+fn load_client_library(
+    lib_path: &str,
+    mapping_path: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
     let client_path = PathBuf::from(lib_path);
 
     // Verify the path
@@ -118,6 +125,21 @@ fn load_client_library(lib_path: &str) -> Result<(), Box<dyn std::error::Error>>
     // Load the new library
     let lib = unsafe { Library::new(&client_path)? };
 
+    // Pass the mapping file before initialization, the client falls back to its own sources otherwise
+    if let Some(mapping_path) = mapping_path {
+        unsafe {
+            if let Ok(set_mapping_fn) =
+                lib.get::<Symbol<unsafe extern "C" fn(*const c_char)>>(b"set_mapping_path")
+            {
+                info!("Passing mapping path: {}", mapping_path);
+                let mapping_path = CString::new(mapping_path)?;
+                set_mapping_fn(mapping_path.as_ptr());
+            } else {
+                error!("Client library does not support custom mapping paths");
+            }
+        }
+    }
+
     // Find and call the initialization function
     unsafe {
         if let Ok(init_fn) = lib.get::<Symbol<extern "C" fn()>>(b"initialize_client") {
@@ -164,7 +186,10 @@ fn unload_client_library() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 // Function to reload the client library
-fn reload_client_library(lib_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+fn reload_client_library(
+    lib_path: &str,
+    mapping_path: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
     info!("Reloading client library");
 
     // Copy the file if necessary to avoid lock issues
@@ -204,7 +229,7 @@ fn reload_client_library(lib_path: &str) -> Result<(), Box<dyn std::error::Error
     });
 
     // Load the new copy
-    load_client_library(temp_client)?;
+    load_client_library(temp_client, mapping_path)?;
 
     // Wait a bit to ensure all resources are released
     thread::sleep(Duration::from_millis(100));
@@ -213,6 +238,35 @@ fn reload_client_library(lib_path: &str) -> Result<(), Box<dyn std::error::Error
     Ok(())
 }
 
+// Runs a report function of the loaded client library, like `mapping_self_check`, and returns its text
+fn client_report(function: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    let lib_guard = CLIENT_LIBRARY.get().unwrap().lock().unwrap();
+    let lib = lib_guard.as_ref().ok_or("No client library loaded")?;
+
+    unsafe {
+        let report_fn = lib.get::<Symbol<extern "C" fn() -> *mut c_char>>(function)?;
+        let free_fn = lib.get::<Symbol<unsafe extern "C" fn(*mut c_char)>>(b"free_report")?;
+
+        let report = report_fn();
+        let text = CStr::from_ptr(report).to_string_lossy().into_owned();
+        free_fn(report);
+        Ok(text)
+    }
+}
+
+// Runs a report function of the client and sends the report back to the command connection
+fn send_client_report(stream: &mut impl Write, function: &[u8]) {
+    let report = client_report(function).unwrap_or_else(|e| {
+        error!("Error during client report: {}", e);
+        e.to_string()
+    });
+    info!("{}", report);
+
+    if let Err(e) = writeln!(stream, "{}", report) {
+        error!("Unable to send the report: {}", e);
+    }
+}
+
 // Start a socket server to listen for commands
 fn start_command_server() {
     thread::spawn(move || {
@@ -244,16 +298,31 @@ fn start_command_server() {
 
                         match parts.first() {
                             Some(&"reload") => {
-                                if let Some(path) = parts.get(1) {
+                                if let Some(args) = parts.get(1) {
+                                    // reload <library path> [--mappings <mapping path>]
+                                    let (path, mapping_path) = match args
+                                        .split_once(MAPPINGS_ARGUMENT)
+                                    {
+                                        Some((path, mapping_path)) => (path, Some(mapping_path)),
+                                        None => (*args, None),
+                                    };
                                     info!("Reload command received with path: {}", path);
 
-                                    if let Err(e) = reload_client_library(path) {
+                                    if let Err(e) = reload_client_library(path, mapping_path) {
                                         error!("Error during reload: {}", e);
                                     }
                                 } else {
                                     error!("Reload command received without path!");
                                 }
                             }
+                            Some(&"check") => {
+                                info!("Check command received");
+                                send_client_report(reader.get_mut(), b"mapping_self_check");
+                            }
+                            Some(&"bench-cache") => {
+                                info!("Cache benchmark command received");
+                                send_client_report(reader.get_mut(), b"mapping_cache_benchmark");
+                            }
                             Some(other) => {
                                 error!("Unknown command: {}", other);
                             }
@@ -0,0 +1,745 @@
+//! Generates typed bindings for the classes and members listed in `bindings.json`
+//!
+//! Every listed member is looked up in the mapping embedded in the library, a missing one fails the build.
+//! The generated code is included by `src/mapping/bindings.rs`.
+//! The mapping is also packed into `mappings.bin`, the copy embedded by `src/mapping/loader.rs`.
+
+use mapping::jni_descriptor::{parse_parameters, remap_descriptor};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+// Mirrors the module path of the library, so the shared files can refer to each other
+#[path = "src/mapping"]
+mod mapping {
+    pub mod jni_descriptor;
+}
+#[path = "src/mapping/packed/format.rs"]
+mod format;
+
+const ALLOWLIST: &str = "bindings.json";
+const MAPPINGS: &str = "../mappings.json";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", ALLOWLIST);
+    println!("cargo:rerun-if-changed={}", MAPPINGS);
+
+    let allowlist = read_json(Path::new(ALLOWLIST));
+    let mapping = read_json(Path::new(MAPPINGS));
+
+    let code = match Generator::new(&allowlist, &mapping).and_then(|generator| generator.generate())
+    {
+        Ok(code) => code,
+        Err(errors) => panic!(
+            "{} does not match {}:\n  {}",
+            ALLOWLIST,
+            MAPPINGS,
+            errors.join("\n  ")
+        ),
+    };
+
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(out.join("bindings.rs"), code).unwrap();
+
+    let packed =
+        format::encode(&mapping).unwrap_or_else(|e| panic!("unable to pack {}: {}", MAPPINGS, e));
+    fs::write(out.join("mappings.bin"), packed).unwrap();
+}
+
+fn read_json(path: &Path) -> Value {
+    let contents = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("unable to read {}: {}", path.display(), e));
+    serde_json::from_str(&contents)
+        .unwrap_or_else(|e| panic!("unable to parse {}: {}", path.display(), e))
+}
+
+/// Class of the allowlist with its entry in the mapping
+struct Class<'a> {
+    name: &'a str,
+    /// The `type` entry, names the generated module
+    class_type: &'a str,
+    mapping: &'a Map<String, Value>,
+    entry: &'a Map<String, Value>,
+}
+
+struct Generator<'a> {
+    classes: Vec<Class<'a>>,
+    /// Runtime and Mojang names of the listed classes to their Mojang name, used to type object fields with
+    /// either kind of descriptor
+    bound_names: BTreeMap<&'a str, &'a str>,
+    /// Runtime class name to Mojang name of every mapped class, used to select overloads
+    mojang_names: BTreeMap<&'a str, &'a str>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(allowlist: &'a Value, mapping: &'a Value) -> Result<Self, Vec<String>> {
+        let allowlist = allowlist
+            .as_object()
+            .ok_or_else(|| vec![format!("{} must be an object", ALLOWLIST)])?;
+        let mapped_classes = mapping
+            .get("classes")
+            .and_then(Value::as_object)
+            .ok_or_else(|| vec![format!("{} has no classes", MAPPINGS)])?;
+
+        let mut errors = Vec::new();
+        let mut classes = Vec::new();
+        let mut bound_names = BTreeMap::new();
+
+        for (name, entry) in allowlist {
+            let entry = match entry.as_object() {
+                Some(entry) => entry,
+                None => {
+                    errors.push(format!("{}: entry must be an object", name));
+                    continue;
+                }
+            };
+            let class_type = match entry.get("type").and_then(Value::as_str) {
+                Some(class_type) => class_type,
+                None => {
+                    errors.push(format!("{}: missing type", name));
+                    continue;
+                }
+            };
+            let class_mapping = match mapped_classes.get(name).and_then(Value::as_object) {
+                Some(class_mapping) => class_mapping,
+                None => {
+                    errors.push(format!("class {} is not mapped", name));
+                    continue;
+                }
+            };
+
+            if let Some(runtime_name) = class_mapping.get("name").and_then(Value::as_str) {
+                bound_names.insert(runtime_name, name.as_str());
+            }
+            bound_names.insert(name.as_str(), name.as_str());
+            classes.push(Class {
+                name,
+                class_type,
+                mapping: class_mapping,
+                entry,
+            });
+        }
+
+        let mojang_names = mapped_classes
+            .iter()
+            .filter_map(|(name, class)| Some((class.get("name")?.as_str()?, name.as_str())))
+            .collect();
+
+        if errors.is_empty() {
+            Ok(Self {
+                classes,
+                bound_names,
+                mojang_names,
+            })
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn generate(&self) -> Result<String, Vec<String>> {
+        let mut errors = Vec::new();
+        let mut code = String::from("// Generated by build.rs from bindings.json, do not edit\n");
+        let mut references = String::new();
+
+        for class in &self.classes {
+            let mut functions = Vec::new();
+
+            for (key, is_static) in [("static_methods", true), ("methods", false)] {
+                for entry in names(class.entry, key) {
+                    match self.method(class, entry, is_static, &mut references) {
+                        Ok(function) => functions.push(function),
+                        Err(e) => errors.push(e),
+                    }
+                }
+            }
+            for entry in names(class.entry, "constructors") {
+                match self.constructor(class, entry, &mut references) {
+                    Ok(function) => functions.push(function),
+                    Err(e) => errors.push(e),
+                }
+            }
+            for (key, is_static) in [("static_fields", true), ("fields", false)] {
+                for name in names(class.entry, key) {
+                    match self.field(class, name, is_static, &mut references) {
+                        Ok(mut field_functions) => functions.append(&mut field_functions),
+                        Err(e) => errors.push(e),
+                    }
+                }
+            }
+
+            let mut defined = HashSet::new();
+            for (name, _) in &functions {
+                if !defined.insert(name) {
+                    errors.push(format!(
+                        "{}: more than one member generates the function {}",
+                        class.name, name
+                    ));
+                }
+            }
+
+            if functions.is_empty() {
+                // Only listed to type object fields
+                continue;
+            }
+
+            writeln!(code, "\n/// Bindings of `{}`", class.name).unwrap();
+            writeln!(
+                code,
+                "pub mod {} {{",
+                escape_keyword(&to_snake_case(class.class_type))
+            )
+            .unwrap();
+            writeln!(code, "    use super::*;").unwrap();
+            for (_, function) in functions {
+                code.push('\n');
+                code.push_str(&function);
+            }
+            code.push_str("}\n");
+        }
+
+        writeln!(
+            code,
+            "\n/// Members of the generated bindings, checked at startup\npub const REFERENCES: &[Reference] = &[\n{}];",
+            references
+        )
+        .unwrap();
+
+        if errors.is_empty() {
+            Ok(code)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Replaces the runtime class names of a descriptor with the Mojang ones
+    fn deobfuscate(&self, descriptor: &str) -> String {
+        remap_descriptor(descriptor, |name| self.mojang_names.get(name))
+    }
+
+    /// Returns the signature of the overload of `name` selected by the parameter count, if any
+    fn overload(
+        &self,
+        class: &Class<'a>,
+        entry: &str,
+        name: &str,
+        parameter_count: Option<usize>,
+    ) -> Result<&'a str, String> {
+        let overloads = match class
+            .mapping
+            .get("methods")
+            .and_then(|methods| methods.get(name))
+        {
+            Some(Value::Array(overloads)) => overloads.iter().collect(),
+            Some(method) => vec![method],
+            None => return Err(format!("method {}.{} is not mapped", class.name, name)),
+        };
+        let signatures: Vec<&str> = overloads
+            .iter()
+            .filter_map(|method| method.get("signature").and_then(Value::as_str))
+            .filter(|signature| {
+                parameter_count
+                    .is_none_or(|count| parse_parameters(signature).map(|p| p.len()) == Some(count))
+            })
+            .collect();
+
+        match signatures.as_slice() {
+            [signature] => Ok(signature),
+            [] => Err(format!(
+                "method {} has no overload with {} parameters",
+                entry,
+                parameter_count.unwrap_or_default()
+            )),
+            _ => Err(format!(
+                "method {}.{} is overloaded, list it as {}/<parameter count>",
+                class.name,
+                entry,
+                entry.split('/').next().unwrap_or(entry)
+            )),
+        }
+    }
+
+    /// Generates the function of a constructor, `entry` is `new` or `new/<parameter count>` for overloads
+    fn constructor(
+        &self,
+        class: &Class<'a>,
+        entry: &str,
+        references: &mut String,
+    ) -> Result<(String, String), String> {
+        let (name, parameter_count) = parse_entry(class, entry)?;
+        if name != "new" {
+            return Err(format!(
+                "{}: constructors are listed as new or new/<parameter count>, not {}",
+                class.name, entry
+            ));
+        }
+        let signature = self.overload(class, entry, "<init>", parameter_count)?;
+        let parameters = parse_parameters(signature)
+            .ok_or_else(|| format!("{}.<init>: invalid signature {}", class.name, signature))?;
+
+        let function_name = match parameter_count {
+            Some(count) => format!("new_{}", count),
+            None => "new".to_string(),
+        };
+        let mut function = format!("    /// `{}.<init>`\n", class.name);
+        write!(
+            function,
+            "    pub fn {}<'a>(mapping: &'a Mapping",
+            function_name
+        )
+        .unwrap();
+        for (index, parameter) in parameters.iter().enumerate() {
+            write!(function, ", arg{}: {}", index, rust_type(parameter, false)).unwrap();
+        }
+        function.push_str(") -> Result<JObject<'a>, MappingError> {\n");
+
+        let args: Vec<String> = (0..parameters.len())
+            .map(|index| format!("JValue::from(arg{})", index))
+            .collect();
+        let call = match parameter_count {
+            Some(_) => format!(
+                "mapping.try_new_object_by_signature(MinecraftClassType::new(\"{}\"), \"{}\", &[{}])",
+                class.name,
+                self.deobfuscate(signature),
+                args.join(", ")
+            ),
+            None => format!(
+                "mapping.try_new_object(MinecraftClassType::new(\"{}\"), &[{}])",
+                class.name,
+                args.join(", ")
+            ),
+        };
+        writeln!(function, "        {}\n    }}", call).unwrap();
+
+        writeln!(
+            references,
+            "    Reference::Method {{ class: MinecraftClassType::new(\"{}\"), name: \"<init>\", is_static: false }},",
+            class.name
+        )
+        .unwrap();
+
+        Ok((function_name, function))
+    }
+
+    /// Generates the function of a method, `entry` is `name` or `name/<parameter count>` for overloads
+    fn method(
+        &self,
+        class: &Class,
+        entry: &str,
+        is_static: bool,
+        references: &mut String,
+    ) -> Result<(String, String), String> {
+        let (name, parameter_count) = parse_entry(class, entry)?;
+        let signature = self.overload(class, entry, name, parameter_count)?;
+
+        let parameters = parse_parameters(signature)
+            .ok_or_else(|| format!("{}.{}: invalid signature {}", class.name, name, signature))?;
+        let return_type = &signature[signature.find(')').unwrap() + 1..];
+
+        let function_name = match parameter_count {
+            Some(count) => format!("{}_{}", to_snake_case(name), count),
+            None => to_snake_case(name),
+        };
+        let returns_object = is_object(return_type);
+
+        let mut function = format!("    /// `{}.{}`\n", class.name, name);
+        write!(
+            function,
+            "    pub fn {}{}(mapping: &{}Mapping",
+            escape_keyword(&function_name),
+            if returns_object { "<'a>" } else { "" },
+            if returns_object { "'a " } else { "" }
+        )
+        .unwrap();
+        if !is_static {
+            function.push_str(", instance: &JObject");
+        }
+        for (index, parameter) in parameters.iter().enumerate() {
+            write!(function, ", arg{}: {}", index, rust_type(parameter, false)).unwrap();
+        }
+        writeln!(
+            function,
+            ") -> Result<{}, MappingError> {{",
+            rust_type(return_type, true)
+        )
+        .unwrap();
+
+        let args: Vec<String> = (0..parameters.len())
+            .map(|index| format!("JValue::from(arg{})", index))
+            .collect();
+        // Overloads are selected by descriptor, the arguments alone can be ambiguous
+        let selector = match parameter_count {
+            Some(_) => format!("{}{}", name, self.deobfuscate(signature)),
+            None => name.to_string(),
+        };
+        let call = if is_static {
+            format!(
+                "mapping.try_call_static_method(MinecraftClassType::new(\"{}\"), \"{}\", &[{}])?",
+                class.name,
+                selector,
+                args.join(", ")
+            )
+        } else {
+            format!(
+                "mapping.try_call_method(MinecraftClassType::new(\"{}\"), instance, \"{}\", &[{}])?",
+                class.name,
+                selector,
+                args.join(", ")
+            )
+        };
+        writeln!(
+            function,
+            "        {}.{}().map_err(MappingError::from)\n    }}",
+            call,
+            value_getter(return_type)
+        )
+        .unwrap();
+
+        writeln!(
+            references,
+            "    Reference::Method {{ class: MinecraftClassType::new(\"{}\"), name: \"{}\", is_static: {} }},",
+            class.name, name, is_static
+        )
+        .unwrap();
+
+        Ok((function_name, function))
+    }
+
+    /// Generates the getter of a field, and the setter of an instance field
+    fn field(
+        &self,
+        class: &Class,
+        name: &str,
+        is_static: bool,
+        references: &mut String,
+    ) -> Result<Vec<(String, String)>, String> {
+        let field = class
+            .mapping
+            .get("fields")
+            .and_then(|fields| fields.get(name))
+            .ok_or_else(|| format!("field {}.{} is not mapped", class.name, name))?;
+        let signature = field
+            .get("signature")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("field {}.{} has no recorded type", class.name, name))?;
+        let field_type = self.field_type(signature).ok_or_else(|| {
+            format!(
+                "field {}.{} has type {}, which has no binding",
+                class.name, name, signature
+            )
+        })?;
+
+        let snake_name = to_snake_case(name);
+        let returns_object = is_object(signature);
+        let mut functions = Vec::new();
+
+        let getter_name = format!("get_{}", snake_name);
+        let mut getter = format!("    /// `{}.{}`\n", class.name, name);
+        write!(
+            getter,
+            "    pub fn {}{}(mapping: &{}Mapping",
+            getter_name,
+            if returns_object { "<'a>" } else { "" },
+            if returns_object { "'a " } else { "" }
+        )
+        .unwrap();
+        let access = if is_static {
+            format!(
+                "mapping.try_get_static_field(MinecraftClassType::new(\"{}\"), \"{}\", {})?",
+                class.name, name, field_type
+            )
+        } else {
+            getter.push_str(", instance: &JObject");
+            format!(
+                "mapping.try_get_field(MinecraftClassType::new(\"{}\"), instance, \"{}\", {})?",
+                class.name, name, field_type
+            )
+        };
+        writeln!(
+            getter,
+            ") -> Result<{}, MappingError> {{\n        {}.{}().map_err(MappingError::from)\n    }}",
+            rust_type(signature, true),
+            access,
+            value_getter(signature)
+        )
+        .unwrap();
+        functions.push((getter_name, getter));
+
+        let setter_name = format!("set_{}", snake_name);
+        let setter = if is_static {
+            format!(
+                "    /// `{class}.{name}`\n    pub fn {setter}(mapping: &Mapping, value: {rust_type}) -> Result<(), MappingError> {{\n        mapping.try_set_static_field(MinecraftClassType::new(\"{class_type}\"), \"{name}\", {field_type}, JValue::from(value))\n    }}\n",
+                class = class.name,
+                setter = setter_name,
+                rust_type = rust_type(signature, false),
+                class_type = class.name,
+            )
+        } else {
+            format!(
+                "    /// `{class}.{name}`\n    pub fn {setter}(mapping: &Mapping, instance: &JObject, value: {rust_type}) -> Result<(), MappingError> {{\n        mapping.try_set_field(MinecraftClassType::new(\"{class_type}\"), instance, \"{name}\", {field_type}, JValue::from(value))\n    }}\n",
+                class = class.name,
+                setter = setter_name,
+                rust_type = rust_type(signature, false),
+                class_type = class.name,
+            )
+        };
+        functions.push((setter_name, setter));
+
+        let closure = if field_type.contains("mapping") {
+            format!("|mapping| {}", field_type)
+        } else {
+            format!("|_| {}", field_type)
+        };
+        writeln!(
+            references,
+            "    Reference::Field {{ class: MinecraftClassType::new(\"{}\"), name: \"{}\", field_type: {} }},",
+            class.name, name, closure
+        )
+        .unwrap();
+
+        Ok(functions)
+    }
+
+    /// Returns the `FieldType` expression of a runtime descriptor
+    fn field_type(&self, descriptor: &str) -> Option<String> {
+        let field_type = match descriptor {
+            "Z" => "FieldType::Boolean",
+            "B" => "FieldType::Byte",
+            "C" => "FieldType::Char",
+            "S" => "FieldType::Short",
+            "I" => "FieldType::Int",
+            "J" => "FieldType::Long",
+            "F" => "FieldType::Float",
+            "D" => "FieldType::Double",
+            "Ljava/lang/String;" => "FieldType::String",
+            _ => {
+                if let Some(element_type) = descriptor.strip_prefix('[') {
+                    return Some(format!(
+                        "FieldType::array({})",
+                        self.field_type(element_type)?
+                    ));
+                }
+                let runtime_name = descriptor.strip_prefix('L')?.strip_suffix(';')?;
+                return Some(match self.bound_names.get(runtime_name) {
+                    Some(name) => format!(
+                        "FieldType::Object(MinecraftClassType::new(\"{}\"), mapping)",
+                        name
+                    ),
+                    None => format!("FieldType::Class({:?})", runtime_name),
+                });
+            }
+        };
+        Some(field_type.to_string())
+    }
+}
+
+/// Splits an entry of the allowlist into the member name and the parameter count of the overload
+fn parse_entry<'e>(class: &Class, entry: &'e str) -> Result<(&'e str, Option<usize>), String> {
+    match entry.split_once('/') {
+        Some((name, count)) => {
+            let count = count
+                .parse::<usize>()
+                .map_err(|_| format!("{}: invalid parameter count in {}", class.name, entry))?;
+            Ok((name, Some(count)))
+        }
+        None => Ok((entry, None)),
+    }
+}
+
+/// Returns the names listed under `key`, which is optional
+fn names<'a>(entry: &'a Map<String, Value>, key: &str) -> impl Iterator<Item = &'a str> {
+    entry
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+}
+
+fn is_object(descriptor: &str) -> bool {
+    descriptor.starts_with('L') || descriptor.starts_with('[')
+}
+
+/// Rust type of a descriptor, returned objects live as long as the mapping
+fn rust_type(descriptor: &str, is_return: bool) -> &'static str {
+    match descriptor {
+        "V" => "()",
+        "Z" => "bool",
+        "B" => "i8",
+        "C" => "u16",
+        "S" => "i16",
+        "I" => "i32",
+        "J" => "i64",
+        "F" => "f32",
+        "D" => "f64",
+        _ if is_return => "JObject<'a>",
+        _ => "&JObject",
+    }
+}
+
+/// `JValueOwned` method converting to the Rust type of a descriptor
+fn value_getter(descriptor: &str) -> &'static str {
+    match descriptor {
+        "V" => "v",
+        "Z" => "z",
+        "B" => "b",
+        "C" => "c",
+        "S" => "s",
+        "I" => "i",
+        "J" => "j",
+        "F" => "f",
+        "D" => "d",
+        _ => "l",
+    }
+}
+
+/// Converts a camel case or constant name, e.g. `getURLName` -> `get_url_name` and `CODEC` -> `codec`
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (index, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() {
+            let previous = index.checked_sub(1).map(|index| chars[index]);
+            let next = chars.get(index + 1);
+            let starts_word = match previous {
+                Some(previous) if previous.is_uppercase() => {
+                    next.is_some_and(|next| next.is_lowercase())
+                }
+                Some(previous) => previous != '_',
+                None => false,
+            };
+            if starts_word {
+                snake.push('_');
+            }
+            snake.extend(ch.to_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
+
+fn escape_keyword(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "break", "const", "continue", "crate", "else", "enum", "extern", "false", "fn",
+        "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+        "return", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+        "while", "async", "await", "dyn", "abstract", "become", "box", "do", "final", "macro",
+        "override", "priv", "typeof", "unsized", "virtual", "yield", "try",
+    ];
+
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("getURLName"), "get_url_name");
+        assert_eq!(to_snake_case("CODEC"), "codec");
+        assert_eq!(to_snake_case("LocalPlayer"), "local_player");
+        assert_eq!(to_snake_case("MAIN_HAND"), "main_hand");
+        assert_eq!(escape_keyword(&to_snake_case("Type")), "r#type");
+        assert_eq!(escape_keyword(&to_snake_case("Box")), "r#box");
+        assert_eq!(escape_keyword("position"), "position");
+    }
+
+    #[test]
+    fn test_generate() {
+        let allowlist = json!({
+            "net/minecraft/world/entity/Entity": {
+                "type": "Entity",
+                "methods": ["position", "move/2"],
+                "fields": ["fallDistance"]
+            },
+            "net/minecraft/world/level/Type": {
+                "type": "Type",
+                "static_methods": ["match"]
+            }
+        });
+        let mapping = json!({
+            "classes": {
+                "net/minecraft/world/entity/Entity": {
+                    "name": "bsr",
+                    "methods": {
+                        "position": {"name": "dt", "signature": "()Lftm;"},
+                        "move": [
+                            {"name": "a", "signature": "(Lbsr;)V"},
+                            {"name": "a", "signature": "(Lbsr;I)V"}
+                        ]
+                    },
+                    "fields": {"fallDistance": {"name": "ab", "signature": "F"}}
+                },
+                "net/minecraft/world/level/Type": {
+                    "name": "dkq",
+                    "methods": {"match": {"name": "a", "signature": "(I)Z"}},
+                    "fields": {}
+                },
+                "net/minecraft/world/phys/Vec3": {"name": "ftm", "methods": {}, "fields": {}}
+            }
+        });
+
+        let code = Generator::new(&allowlist, &mapping)
+            .and_then(|generator| generator.generate())
+            .unwrap();
+        assert!(code.contains("pub mod entity {"));
+        assert!(code.contains("pub mod r#type {"));
+        assert!(code.contains(
+            "pub fn r#match(mapping: &Mapping, arg0: i32) -> Result<bool, MappingError>"
+        ));
+        assert!(code.contains(
+            "pub fn move_2(mapping: &Mapping, instance: &JObject, arg0: &JObject, arg1: i32)"
+        ));
+        // Overloads are selected by the descriptor with Mojang names
+        assert!(code.contains("\"move(Lnet/minecraft/world/entity/Entity;I)V\""));
+        assert!(code.contains("pub fn get_fall_distance(mapping: &Mapping, instance: &JObject) -> Result<f32, MappingError>"));
+        assert!(code.contains("Reference::Method { class: MinecraftClassType::new(\"net/minecraft/world/level/Type\"), name: \"match\", is_static: true },"));
+    }
+
+    #[test]
+    fn test_generate_errors() {
+        let allowlist = json!({
+            "net/minecraft/world/entity/Entity": {
+                "type": "Entity",
+                "methods": ["move", "jump"]
+            },
+            "net/minecraft/Missing": {"type": "Missing"}
+        });
+        let mapping = json!({
+            "classes": {
+                "net/minecraft/world/entity/Entity": {
+                    "name": "bsr",
+                    "methods": {
+                        "move": [
+                            {"name": "a", "signature": "(Lbsr;)V"},
+                            {"name": "a", "signature": "(Lbsr;I)V"}
+                        ]
+                    },
+                    "fields": {}
+                }
+            }
+        });
+
+        let errors = Generator::new(&allowlist, &mapping).err().unwrap();
+        assert_eq!(errors, ["class net/minecraft/Missing is not mapped"]);
+
+        let allowlist = json!({"net/minecraft/world/entity/Entity": allowlist["net/minecraft/world/entity/Entity"]});
+        let errors = Generator::new(&allowlist, &mapping)
+            .and_then(|generator| generator.generate())
+            .unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("is overloaded, list it as move/<parameter count>"));
+        assert_eq!(
+            errors[1],
+            "method net/minecraft/world/entity/Entity.jump is not mapped"
+        );
+    }
+}
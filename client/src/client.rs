@@ -1,8 +1,11 @@
+use crate::mapping::error::MappingError;
 use crate::module::{Module, ModuleType};
 use crate::LogExpect;
 use jni::sys::{jsize, JNI_GetCreatedJavaVMs, JNI_OK};
 use jni::{JNIEnv, JavaVM};
+use log::error;
 use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 #[derive(Debug)]
@@ -39,7 +42,7 @@ impl DarkClient {
         })
     }
 
-    pub fn get_env(&self) -> jni::errors::Result<JNIEnv> {
+    pub fn get_env(&self) -> jni::errors::Result<JNIEnv<'_>> {
         //self.jvm.attach_current_thread()
         self.jvm.attach_current_thread_as_daemon()
     }
@@ -54,16 +57,54 @@ impl DarkClient {
         for module in modules.values() {
             let module = module.lock().unwrap();
             if module.get_module_data().enabled {
-                module.on_tick();
+                self.run_guarded(&module.get_module_data().name, || module.on_tick());
+            }
+        }
+    }
+
+    /// Runs a module callback, so that a panic or a Java exception left pending by the module
+    /// is logged instead of ending the calling thread or breaking its next JNI calls
+    pub fn run_guarded<F: FnOnce()>(&self, module_name: &str, callback: F) {
+        if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(callback)) {
+            let message = panic
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| panic.downcast_ref::<&str>().copied())
+                .unwrap_or("unknown panic");
+            error!("Module {} panicked: {}", module_name, message);
+        }
+
+        if let Ok(mut env) = self.get_env() {
+            if let Some(exception) = MappingError::take_exception(&mut env) {
+                error!(
+                    "Module {} left a Java exception pending: {}",
+                    module_name, exception
+                );
             }
         }
     }
 }
 
+// The JVM is not available when running tests, so the symbol is provided here instead
+#[cfg(test)]
+mod jvm_stub {
+    use jni::sys::{jint, jsize, JavaVM, JNI_ERR};
+
+    #[no_mangle]
+    extern "system" fn JNI_GetCreatedJavaVMs(
+        _vm_buf: *mut *mut JavaVM,
+        _buf_len: jsize,
+        _n_vms: *mut jsize,
+    ) -> jint {
+        JNI_ERR
+    }
+}
+
 // Module for handling keyboard inputs
 pub mod keyboard {
     use super::*;
     use crate::mapping::client::minecraft::Minecraft;
+    use crate::mapping::{Mapping, MinecraftClassType};
     use jni::objects::JValue;
     use jni::sys::jlong;
     use log::info;
@@ -74,6 +115,8 @@ pub mod keyboard {
 
     static RUNNING: OnceLock<AtomicBool> = OnceLock::new();
 
+    const GLFW: MinecraftClassType = MinecraftClassType::unmapped("org/lwjgl/glfw/GLFW");
+
     pub fn start_keyboard_handler() {
         if RUNNING.get().is_none() {
             RUNNING.set(AtomicBool::new(true)).unwrap();
@@ -81,9 +124,18 @@ pub mod keyboard {
         thread::spawn(|| {
             let minecraft = Minecraft::instance();
             let client = DarkClient::instance();
-            let mut env = client.get_env().unwrap();
-
-            let glfw_window = minecraft.window.get_window();
+            let mapping = minecraft.get_mapping();
+
+            let glfw_window = match minecraft.window.get_window() {
+                Ok(window) => window,
+                Err(e) => {
+                    error!(
+                        "Unable to get the game window, key binds are disabled: {}",
+                        e
+                    );
+                    return;
+                }
+            };
 
             let mut keys: HashSet<i32> = HashSet::new();
             while RUNNING
@@ -98,7 +150,14 @@ pub mod keyboard {
                     let module_data = module.get_module_data();
                     let key = module_data.key_bind as i32;
 
-                    if is_key_down(&mut env, glfw_window, key) {
+                    let key_down = match is_key_down(mapping, glfw_window, key) {
+                        Ok(key_down) => key_down,
+                        Err(e) => {
+                            error!("Unable to read key {}: {}", key, e);
+                            false
+                        }
+                    };
+                    if key_down {
                         if !keys.contains(&key) {
                             keys.insert(key);
 
@@ -108,11 +167,14 @@ pub mod keyboard {
                                 module_data.name,
                                 if enabled { "enabled" } else { "disabled" }
                             );
-                            if enabled {
-                                module.on_start();
-                            } else {
-                                module.on_stop();
-                            }
+                            let name = module_data.name.clone();
+                            client.run_guarded(&name, || {
+                                if enabled {
+                                    module.on_start();
+                                } else {
+                                    module.on_stop();
+                                }
+                            });
                             module.get_module_data_mut().set_enabled(enabled);
                         }
                     } else {
@@ -133,17 +195,14 @@ pub mod keyboard {
             .store(false, std::sync::atomic::Ordering::Relaxed);
     }
 
-    fn is_key_down(env: &mut JNIEnv, glfw_window: jlong, key: i32) -> bool {
-        let glfw = env.find_class("org/lwjgl/glfw/GLFW").unwrap();
-        env.call_static_method(
-            glfw,
-            "glfwGetKey",
-            "(JI)I",
-            &[JValue::Long(glfw_window), JValue::Int(key)],
-        )
-        .unwrap()
-        .i()
-        .unwrap()
-            == 1
+    fn is_key_down(mapping: &Mapping, glfw_window: jlong, key: i32) -> Result<bool, MappingError> {
+        let state = mapping
+            .try_call_static_method(
+                GLFW,
+                "glfwGetKey",
+                &[JValue::Long(glfw_window), JValue::Int(key)],
+            )?
+            .i()?;
+        Ok(state == 1)
     }
 }
@@ -2,16 +2,19 @@
 
 extern crate jni;
 mod client;
-mod mapping;
+pub mod mapping;
 mod module;
 
 use crate::client::keyboard::{start_keyboard_handler, stop_keyboard_handler};
 use crate::client::DarkClient;
 use crate::mapping::client::minecraft::Minecraft;
+use crate::mapping::MinecraftClassType;
 use crate::module::{FlyModule, ModuleType};
-use log::{error, info, LevelFilter};
+use log::{error, info, warn, LevelFilter};
 use simplelog::{Config, WriteLogger};
+use std::ffi::{c_char, CStr, CString};
 use std::fs::File;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
@@ -48,6 +51,65 @@ impl<T> LogExpect<T> for Option<T> {
     }
 }
 
+/// Sets the mapping file to use, must be called by the agent before `initialize_client`
+///
+/// # Safety
+/// `path` must be a valid nul-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn set_mapping_path(path: *const c_char) {
+    if path.is_null() {
+        return;
+    }
+
+    let path = CStr::from_ptr(path).to_string_lossy().into_owned();
+    mapping::loader::set_agent_mapping_path(PathBuf::from(path));
+}
+
+/// Runs the mapping self-check and returns the report, which must be freed with `free_report`
+#[no_mangle]
+pub extern "C" fn mapping_self_check() -> *mut c_char {
+    let report = match Minecraft::get_initialized() {
+        Some(minecraft) => minecraft.get_mapping().self_check().to_string(),
+        None => String::from("Minecraft is not initialized"),
+    };
+
+    CString::new(report.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Times calls of `Entity.position` on the player with and without the resolution cache and returns the
+/// report, which must be freed with `free_report`
+#[no_mangle]
+pub extern "C" fn mapping_cache_benchmark() -> *mut c_char {
+    let report = match Minecraft::get_initialized() {
+        Some(minecraft) => mapping::cache::measure_speedup(
+            minecraft.get_mapping(),
+            MinecraftClassType::Entity,
+            minecraft.player.entity.jni_entity.as_obj(),
+            "position",
+            10_000,
+        )
+        .unwrap_or_else(|e| format!("Unable to measure the cache: {}", e)),
+        None => String::from("Minecraft is not initialized"),
+    };
+
+    CString::new(report.replace('\0', ""))
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Frees a report returned by a report function of the client, `mapping_self_check` or `mapping_cache_benchmark`
+///
+/// # Safety
+/// `report` must come from one of these functions and must not be used afterward
+#[no_mangle]
+pub unsafe extern "C" fn free_report(report: *mut c_char) {
+    if !report.is_null() {
+        drop(CString::from_raw(report));
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn initialize_client() {
     // Make sure we can't initialize more than once
@@ -68,7 +130,20 @@ pub extern "C" fn initialize_client() {
 
     thread::spawn(|| {
         info!("Starting DarkClient...");
-        let minecraft = Minecraft::instance();
+        let minecraft = match Minecraft::try_instance() {
+            Ok(minecraft) => minecraft,
+            Err(e) => {
+                error!("{}, modules will not be started", e);
+                return;
+            }
+        };
+
+        let report = minecraft.get_mapping().self_check();
+        if report.is_ok() {
+            info!("{}", report);
+        } else {
+            warn!("{}", report);
+        }
 
         register_modules(minecraft);
 
@@ -89,10 +164,10 @@ pub extern "C" fn initialize_client() {
         let mut tick_lock = tick_thread().lock().unwrap();
         *tick_lock = Some(thread_handle);
 
-        info!(
-            "Player position: {:?}",
-            minecraft.player.entity.get_position()
-        );
+        match minecraft.player.entity.get_position() {
+            Ok(position) => info!("Player position: {:?}", position),
+            Err(e) => error!("Unable to read the player position: {}", e),
+        }
     });
 }
 
@@ -120,6 +195,11 @@ pub extern "C" fn cleanup_client() {
         }
     }
 
+    // The cached classes and IDs can't outlive a reload of the client or the mapping
+    if let Some(minecraft) = Minecraft::get_initialized() {
+        minecraft.get_mapping().clear_cache();
+    }
+
     // Clean up other resources if necessary
     info!("Client cleanup completed");
 }
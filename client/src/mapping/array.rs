@@ -0,0 +1,214 @@
+use crate::mapping::error::MappingError;
+use crate::mapping::{FieldType, Mapping, MinecraftClassType};
+use jni::errors::Result as JniResult;
+use jni::objects::{
+    JBooleanArray, JByteArray, JCharArray, JDoubleArray, JFloatArray, JIntArray, JLongArray,
+    JObject, JShortArray, JValue,
+};
+use jni::JNIEnv;
+
+/// Element of a Java primitive array, copied with a single JNI region call
+pub trait ArrayElement: Copy + Default {
+    fn field_type() -> FieldType<'static>;
+
+    fn read_array(env: &mut JNIEnv, array: &JObject) -> JniResult<Vec<Self>>;
+
+    fn new_array<'local>(env: &mut JNIEnv<'local>, values: &[Self]) -> JniResult<JObject<'local>>;
+}
+
+macro_rules! array_element {
+    ($type:ty, $field_type:ident, $array:ident, $get_region:ident, $new_array:ident, $set_region:ident) => {
+        impl ArrayElement for $type {
+            fn field_type() -> FieldType<'static> {
+                FieldType::$field_type
+            }
+
+            fn read_array(env: &mut JNIEnv, array: &JObject) -> JniResult<Vec<Self>> {
+                let array = <&$array>::from(array);
+                let mut values = vec![Self::default(); env.get_array_length(array)? as usize];
+                env.$get_region(array, 0, &mut values)?;
+                Ok(values)
+            }
+
+            fn new_array<'local>(
+                env: &mut JNIEnv<'local>,
+                values: &[Self],
+            ) -> JniResult<JObject<'local>> {
+                let array = env.$new_array(values.len() as i32)?;
+                env.$set_region(&array, 0, values)?;
+                Ok(array.into())
+            }
+        }
+    };
+}
+
+array_element!(
+    i8,
+    Byte,
+    JByteArray,
+    get_byte_array_region,
+    new_byte_array,
+    set_byte_array_region
+);
+array_element!(
+    u16,
+    Char,
+    JCharArray,
+    get_char_array_region,
+    new_char_array,
+    set_char_array_region
+);
+array_element!(
+    i16,
+    Short,
+    JShortArray,
+    get_short_array_region,
+    new_short_array,
+    set_short_array_region
+);
+array_element!(
+    i32,
+    Int,
+    JIntArray,
+    get_int_array_region,
+    new_int_array,
+    set_int_array_region
+);
+array_element!(
+    i64,
+    Long,
+    JLongArray,
+    get_long_array_region,
+    new_long_array,
+    set_long_array_region
+);
+array_element!(
+    f32,
+    Float,
+    JFloatArray,
+    get_float_array_region,
+    new_float_array,
+    set_float_array_region
+);
+array_element!(
+    f64,
+    Double,
+    JDoubleArray,
+    get_double_array_region,
+    new_double_array,
+    set_double_array_region
+);
+
+/// `jboolean` is a `u8`, so the region is copied as bytes and converted
+impl ArrayElement for bool {
+    fn field_type() -> FieldType<'static> {
+        FieldType::Boolean
+    }
+
+    fn read_array(env: &mut JNIEnv, array: &JObject) -> JniResult<Vec<Self>> {
+        let array = <&JBooleanArray>::from(array);
+        let mut values = vec![0; env.get_array_length(array)? as usize];
+        env.get_boolean_array_region(array, 0, &mut values)?;
+        Ok(values.into_iter().map(|value| value != 0).collect())
+    }
+
+    fn new_array<'local>(env: &mut JNIEnv<'local>, values: &[Self]) -> JniResult<JObject<'local>> {
+        let bytes: Vec<u8> = values.iter().map(|value| *value as u8).collect();
+        let array = env.new_boolean_array(values.len() as i32)?;
+        env.set_boolean_array_region(&array, 0, &bytes)?;
+        Ok(array.into())
+    }
+}
+
+impl Mapping {
+    /// Copies a Java primitive array into a `Vec`, failing on `null`
+    pub fn try_read_array<T: ArrayElement>(&self, array: &JObject) -> Result<Vec<T>, MappingError> {
+        if array.is_null() {
+            return Err(MappingError::Jni(jni::errors::Error::NullPtr("read_array")));
+        }
+
+        let mut env = self.try_get_env()?;
+        T::read_array(&mut env, array).map_err(|e| self.java_error(&mut env, e))
+    }
+
+    /// Creates a Java primitive array holding a copy of `values`
+    pub fn try_new_array<T: ArrayElement>(
+        &self,
+        values: &[T],
+    ) -> Result<JObject<'_>, MappingError> {
+        let mut env = self.try_get_env()?;
+        T::new_array(&mut env, values).map_err(|e| self.java_error(&mut env, e))
+    }
+
+    /// Reads a primitive array field, e.g. `i32` for an `int[]` field
+    pub fn try_get_array_field<T: ArrayElement>(
+        &self,
+        class_type: MinecraftClassType,
+        instance: &JObject,
+        field_name: &str,
+    ) -> Result<Vec<T>, MappingError> {
+        let array = self
+            .try_get_field(
+                class_type,
+                instance,
+                field_name,
+                FieldType::array(T::field_type()),
+            )?
+            .l()?;
+        self.read_local_array(array)
+    }
+
+    pub fn try_get_static_array_field<T: ArrayElement>(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+    ) -> Result<Vec<T>, MappingError> {
+        let array = self
+            .try_get_static_field(class_type, field_name, FieldType::array(T::field_type()))?
+            .l()?;
+        self.read_local_array(array)
+    }
+
+    /// Replaces a primitive array field with a new array holding `values`
+    pub fn try_set_array_field<T: ArrayElement>(
+        &self,
+        class_type: MinecraftClassType,
+        instance: &JObject,
+        field_name: &str,
+        values: &[T],
+    ) -> Result<(), MappingError> {
+        let array = self.try_new_array(values)?;
+        let result = self.try_set_field(
+            class_type,
+            instance,
+            field_name,
+            FieldType::array(T::field_type()),
+            JValue::Object(&array),
+        );
+        self.try_get_env()?.delete_local_ref(array)?;
+        result
+    }
+
+    pub fn try_set_static_array_field<T: ArrayElement>(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+        values: &[T],
+    ) -> Result<(), MappingError> {
+        let array = self.try_new_array(values)?;
+        let result = self.try_set_static_field(
+            class_type,
+            field_name,
+            FieldType::array(T::field_type()),
+            JValue::Object(&array),
+        );
+        self.try_get_env()?.delete_local_ref(array)?;
+        result
+    }
+
+    fn read_local_array<T: ArrayElement>(&self, array: JObject) -> Result<Vec<T>, MappingError> {
+        let values = self.try_read_array(&array);
+        self.try_get_env()?.delete_local_ref(array)?;
+        values
+    }
+}
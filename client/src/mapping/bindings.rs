@@ -0,0 +1,17 @@
+//! Typed accessors generated by `build.rs` for the members listed in `bindings.json`
+//!
+//! Each class has a module named after its `type` entry with one function per method,
+//! and a getter and a setter per field. Listing a member that is not in the mapping fails the build.
+
+use crate::mapping::check::Reference;
+use crate::mapping::error::MappingError;
+use crate::mapping::{FieldType, Mapping, MinecraftClassType};
+use jni::objects::{JObject, JValue};
+
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+/// The generator is compiled again with the library to run its tests, with its own copy of the shared files
+#[cfg(test)]
+#[allow(dead_code, clippy::duplicate_mod)]
+#[path = "../../build.rs"]
+mod build_script;
@@ -0,0 +1,405 @@
+use crate::mapping::class_loader::{context_class_loader, game_class_loader, load_class};
+use crate::mapping::error::MappingError;
+use crate::mapping::reflection::reflect_class;
+use crate::mapping::{Mapping, MinecraftClass, MinecraftClassType};
+use jni::objects::{
+    GlobalRef, JClass, JFieldID, JMethodID, JObject, JStaticFieldID, JStaticMethodID, JValueOwned,
+};
+use jni::signature::{JavaType, ReturnType, TypeSignature};
+use jni::JNIEnv;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+/// Resolved instance method with the parsed parts of its signature needed for unchecked calls
+#[derive(Debug, Clone)]
+pub struct CachedMethod {
+    pub id: JMethodID,
+    pub arg_count: usize,
+    pub return_type: ReturnType,
+}
+
+/// Resolved static method, see [`CachedMethod`]
+#[derive(Debug, Clone)]
+pub struct CachedStaticMethod {
+    pub id: JStaticMethodID,
+    pub arg_count: usize,
+    pub return_type: ReturnType,
+}
+
+/// Resolved instance field with its parsed type
+#[derive(Clone)]
+pub struct CachedField {
+    pub id: JFieldID,
+    pub return_type: ReturnType,
+}
+
+/// `JFieldID` doesn't implement `Debug`, its raw pointer is printed instead
+impl fmt::Debug for CachedField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedField")
+            .field("id", &self.id.into_raw())
+            .field("return_type", &self.return_type)
+            .finish()
+    }
+}
+
+/// Resolved static field with its parsed type
+#[derive(Debug, Clone)]
+pub struct CachedStaticField {
+    pub id: JStaticFieldID,
+    pub java_type: JavaType,
+}
+
+/// Overloads of a member keyed by signature
+type Overloads<V> = HashMap<String, V>;
+
+/// Members keyed by class type, member name and signature, nested so lookups don't allocate
+struct MemberCache<V> {
+    entries: RwLock<HashMap<MinecraftClassType, HashMap<String, Overloads<V>>>>,
+}
+
+impl<V: Clone> MemberCache<V> {
+    fn get(&self, class_type: MinecraftClassType, name: &str, signature: &str) -> Option<V> {
+        self.entries
+            .read()
+            .unwrap()
+            .get(&class_type)?
+            .get(name)?
+            .get(signature)
+            .cloned()
+    }
+
+    fn get_or_resolve<F>(
+        &self,
+        class_type: MinecraftClassType,
+        name: &str,
+        signature: &str,
+        resolve: F,
+    ) -> jni::errors::Result<V>
+    where
+        F: FnOnce() -> jni::errors::Result<V>,
+    {
+        if let Some(value) = self.get(class_type, name, signature) {
+            return Ok(value);
+        }
+
+        let value = resolve()?;
+        self.entries
+            .write()
+            .unwrap()
+            .entry(class_type)
+            .or_default()
+            .entry(name.to_string())
+            .or_default()
+            .insert(signature.to_string(), value.clone());
+        Ok(value)
+    }
+
+    fn len(&self) -> usize {
+        self.entries
+            .read()
+            .unwrap()
+            .values()
+            .flat_map(HashMap::values)
+            .map(HashMap::len)
+            .sum()
+    }
+
+    fn clear(&self) {
+        self.entries.write().unwrap().clear();
+    }
+}
+
+impl<V> Default for MemberCache<V> {
+    fn default() -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+/// Cache of the JNI handles resolved by [`crate::mapping::Mapping`]
+///
+/// Classes are kept as global references, which also keeps the cached method and field IDs valid.
+#[derive(Default)]
+pub struct ResolutionCache {
+    classes: RwLock<HashMap<MinecraftClassType, GlobalRef>>,
+    /// Class loader of the game, `find_class` would use the system class loader
+    class_loader: RwLock<Option<GlobalRef>>,
+    /// Members of the unmapped classes, read through reflection
+    reflected: RwLock<HashMap<MinecraftClassType, Arc<MinecraftClass>>>,
+    methods: MemberCache<CachedMethod>,
+    static_methods: MemberCache<CachedStaticMethod>,
+    fields: MemberCache<CachedField>,
+    static_fields: MemberCache<CachedStaticField>,
+    /// Constants of the enums converted with `java_enum!`, in the order of their Rust variants
+    enum_constants: RwLock<HashMap<MinecraftClassType, Arc<[GlobalRef]>>>,
+}
+
+impl ResolutionCache {
+    /// Returns the class of `class_type`, loading `runtime_name` on the first call
+    pub fn class(
+        &self,
+        env: &mut JNIEnv,
+        class_type: MinecraftClassType,
+        runtime_name: &str,
+    ) -> jni::errors::Result<GlobalRef> {
+        if let Some(class) = self.classes.read().unwrap().get(&class_type) {
+            return Ok(class.clone());
+        }
+
+        let class = self.load_class(env, runtime_name)?;
+        let class = env.new_global_ref(class)?;
+        self.classes
+            .write()
+            .unwrap()
+            .insert(class_type, class.clone());
+        Ok(class)
+    }
+
+    /// Loads a class by runtime name through the game class loader, without caching it
+    pub fn load_class<'local>(
+        &self,
+        env: &mut JNIEnv<'local>,
+        runtime_name: &str,
+    ) -> jni::errors::Result<JObject<'local>> {
+        let class_loader = self.class_loader(env)?;
+        load_class(env, class_loader.as_obj(), runtime_name)
+    }
+
+    /// Returns the captured game class loader, or the context class loader of the game thread
+    fn class_loader(&self, env: &mut JNIEnv) -> jni::errors::Result<GlobalRef> {
+        if let Some(class_loader) = self.class_loader.read().unwrap().as_ref() {
+            return Ok(class_loader.clone());
+        }
+
+        let class_loader = context_class_loader(env)?;
+        let class_loader = env.new_global_ref(class_loader)?;
+        *self.class_loader.write().unwrap() = Some(class_loader.clone());
+        Ok(class_loader)
+    }
+
+    /// Uses the class loader that defined the game class `probe` for every class loaded from now on
+    pub fn capture_class_loader(
+        &self,
+        env: &mut JNIEnv,
+        probe: &str,
+    ) -> jni::errors::Result<GlobalRef> {
+        let class_loader = game_class_loader(env, probe)?;
+        let class_loader = env.new_global_ref(class_loader)?;
+        *self.class_loader.write().unwrap() = Some(class_loader.clone());
+        Ok(class_loader)
+    }
+
+    /// Returns the members of an unmapped class, read through reflection on the first call
+    pub fn reflected_class(
+        &self,
+        env: &mut JNIEnv,
+        class_type: MinecraftClassType,
+    ) -> jni::errors::Result<Arc<MinecraftClass>> {
+        if let Some(class) = self.reflected.read().unwrap().get(&class_type) {
+            return Ok(class.clone());
+        }
+
+        let jclass = self.class(env, class_type, class_type.get_name())?;
+        let class = Arc::new(reflect_class(
+            env,
+            <&JClass>::from(jclass.as_obj()),
+            class_type.get_name(),
+        )?);
+        self.reflected
+            .write()
+            .unwrap()
+            .insert(class_type, class.clone());
+        Ok(class)
+    }
+
+    pub fn method(
+        &self,
+        env: &mut JNIEnv,
+        class_type: MinecraftClassType,
+        runtime_class: &str,
+        name: &str,
+        runtime_name: &str,
+        signature: &str,
+    ) -> jni::errors::Result<CachedMethod> {
+        self.methods
+            .get_or_resolve(class_type, name, signature, || {
+                let class = self.class(env, class_type, runtime_class)?;
+                let parsed = TypeSignature::from_str(signature)?;
+                Ok(CachedMethod {
+                    id: env.get_method_id(
+                        <&JClass>::from(class.as_obj()),
+                        runtime_name,
+                        signature,
+                    )?,
+                    arg_count: parsed.args.len(),
+                    return_type: parsed.ret,
+                })
+            })
+    }
+
+    pub fn static_method(
+        &self,
+        env: &mut JNIEnv,
+        class_type: MinecraftClassType,
+        runtime_class: &str,
+        name: &str,
+        runtime_name: &str,
+        signature: &str,
+    ) -> jni::errors::Result<CachedStaticMethod> {
+        self.static_methods
+            .get_or_resolve(class_type, name, signature, || {
+                let class = self.class(env, class_type, runtime_class)?;
+                let parsed = TypeSignature::from_str(signature)?;
+                Ok(CachedStaticMethod {
+                    id: env.get_static_method_id(
+                        <&JClass>::from(class.as_obj()),
+                        runtime_name,
+                        signature,
+                    )?,
+                    arg_count: parsed.args.len(),
+                    return_type: parsed.ret,
+                })
+            })
+    }
+
+    pub fn field(
+        &self,
+        env: &mut JNIEnv,
+        class_type: MinecraftClassType,
+        runtime_class: &str,
+        name: &str,
+        runtime_name: &str,
+        signature: &str,
+    ) -> jni::errors::Result<CachedField> {
+        self.fields.get_or_resolve(class_type, name, signature, || {
+            let class = self.class(env, class_type, runtime_class)?;
+            Ok(CachedField {
+                id: env.get_field_id(<&JClass>::from(class.as_obj()), runtime_name, signature)?,
+                return_type: ReturnType::from_str(signature)?,
+            })
+        })
+    }
+
+    pub fn static_field(
+        &self,
+        env: &mut JNIEnv,
+        class_type: MinecraftClassType,
+        runtime_class: &str,
+        name: &str,
+        runtime_name: &str,
+        signature: &str,
+    ) -> jni::errors::Result<CachedStaticField> {
+        self.static_fields
+            .get_or_resolve(class_type, name, signature, || {
+                let class = self.class(env, class_type, runtime_class)?;
+                Ok(CachedStaticField {
+                    id: env.get_static_field_id(
+                        <&JClass>::from(class.as_obj()),
+                        runtime_name,
+                        signature,
+                    )?,
+                    java_type: JavaType::from_str(signature)?,
+                })
+            })
+    }
+
+    /// Returns the constants of an enum, read with `resolve` on the first call
+    pub fn enum_constants<F>(
+        &self,
+        class_type: MinecraftClassType,
+        resolve: F,
+    ) -> Result<Arc<[GlobalRef]>, MappingError>
+    where
+        F: FnOnce() -> Result<Vec<GlobalRef>, MappingError>,
+    {
+        if let Some(constants) = self.enum_constants.read().unwrap().get(&class_type) {
+            return Ok(constants.clone());
+        }
+
+        let constants: Arc<[GlobalRef]> = resolve()?.into();
+        self.enum_constants
+            .write()
+            .unwrap()
+            .insert(class_type, constants.clone());
+        Ok(constants)
+    }
+
+    /// Drops every cached handle, must be called when the mapping or the game classes change
+    pub fn clear(&self) {
+        self.classes.write().unwrap().clear();
+        *self.class_loader.write().unwrap() = None;
+        self.reflected.write().unwrap().clear();
+        self.methods.clear();
+        self.static_methods.clear();
+        self.fields.clear();
+        self.static_fields.clear();
+        self.enum_constants.write().unwrap().clear();
+    }
+}
+
+impl fmt::Debug for ResolutionCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolutionCache")
+            .field("classes", &self.classes.read().unwrap().len())
+            .field("reflected", &self.reflected.read().unwrap().len())
+            .field("methods", &self.methods.len())
+            .field("static_methods", &self.static_methods.len())
+            .field("fields", &self.fields.len())
+            .field("static_fields", &self.static_fields.len())
+            .field("enum_constants", &self.enum_constants.read().unwrap().len())
+            .finish()
+    }
+}
+
+/// Times `iterations` calls of a method without arguments, resolved by name every time as done before the cache
+/// and then through the cache
+///
+/// Only run by the `bench-cache` agent command, the calls block the calling thread.
+pub fn measure_speedup(
+    mapping: &Mapping,
+    class_type: MinecraftClassType,
+    instance: &JObject,
+    method_name: &str,
+    iterations: u32,
+) -> Result<String, MappingError> {
+    let mut env = mapping.try_get_env()?;
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        let method = mapping.resolve_method(class_type, method_name, &[])?;
+        let value = env
+            .call_method(instance, &method.name, &method.signature, &[])
+            .map_err(|e| mapping.java_error(&mut env, e))?;
+        delete_local_result(&mut env, value);
+    }
+    let uncached = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        let value = mapping.try_call_method(class_type, instance, method_name, &[])?;
+        delete_local_result(&mut env, value);
+    }
+    let cached = start.elapsed();
+
+    Ok(format!(
+        "{} x {}.{}: {:?} uncached, {:?} cached ({:.1}x faster)",
+        iterations,
+        class_type,
+        method_name,
+        uncached,
+        cached,
+        uncached.as_secs_f64() / cached.as_secs_f64().max(f64::EPSILON)
+    ))
+}
+
+/// Frees the local reference returned by a call, the measured loops would fill the local frame
+fn delete_local_result(env: &mut JNIEnv, value: JValueOwned) {
+    if let Ok(object) = value.l() {
+        let _ = env.delete_local_ref(object);
+    }
+}
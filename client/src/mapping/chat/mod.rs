@@ -0,0 +1,189 @@
+use crate::mapping::bindings::{component, component_serialization};
+use crate::mapping::error::MappingError;
+use crate::mapping::java::{convert_local, FromJava, IntoJava, JavaList};
+use crate::mapping::{FieldType, GameContext, Mapping, MinecraftClassType};
+use jni::objects::{GlobalRef, JObject, JValue};
+use serde_json::Value;
+
+pub mod text;
+
+pub use text::{ClickEvent, RichText, Span, Style, TextColor};
+
+// Libraries of the game, their names are never obfuscated
+const CODEC: MinecraftClassType = MinecraftClassType::unmapped("com/mojang/serialization/Codec");
+const DATA_RESULT: MinecraftClassType =
+    MinecraftClassType::unmapped("com/mojang/serialization/DataResult");
+const JSON_OPS: MinecraftClassType =
+    MinecraftClassType::unmapped("com/mojang/serialization/JsonOps");
+const JSON_PARSER: MinecraftClassType = MinecraftClassType::unmapped("com/google/gson/JsonParser");
+const JSON_ELEMENT: MinecraftClassType =
+    MinecraftClassType::unmapped("com/google/gson/JsonElement");
+
+/// `net.minecraft.network.chat.Component`, the text of chat messages, names and tooltips
+///
+/// Components are converted to and from their JSON form with `ComponentSerialization.CODEC`, so every
+/// content type and style of the game version is supported.
+#[derive(Debug, Clone)]
+pub struct Component {
+    pub jni_component: GlobalRef,
+}
+
+impl GameContext for Component {}
+
+impl FromJava for Component {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        GlobalRef::from_java(mapping, object).map(Component::new)
+    }
+}
+
+impl IntoJava for Component {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+        let mut env = mapping.try_get_env()?;
+        env.new_local_ref(&self.jni_component)
+            .map_err(|e| mapping.java_error(&mut env, e))
+    }
+}
+
+/// Reads the spans of a component with `toFlatList`, which translates the translatable components
+impl FromJava for RichText {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        let flat = component::to_flat_list_0(mapping, object)?;
+        let flat = convert_local(mapping, flat, JavaList::from_java)?;
+
+        let mut text = RichText::new();
+        for literal in flat.iter::<LiteralText>()? {
+            text.spans.append(&mut literal?.0.spans);
+        }
+        Ok(text)
+    }
+}
+
+impl IntoJava for RichText {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+        decode_json(mapping, &self.to_json())
+    }
+}
+
+/// Element of `toFlatList`, a literal component without children
+struct LiteralText(RichText);
+
+impl FromJava for LiteralText {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        let json = encode_json(mapping, object)?;
+        RichText::from_json(&json)
+            .map(LiteralText)
+            .map_err(invalid_component)
+    }
+}
+
+impl Component {
+    pub fn new(jni_component: GlobalRef) -> Self {
+        Component { jni_component }
+    }
+
+    /// Creates a component from rich text, e.g. to display it with `LocalPlayer::display_message`
+    pub fn from_text(mapping: &Mapping, text: &RichText) -> Result<Self, MappingError> {
+        convert_local(mapping, text.to_java(mapping)?, Component::from_java)
+    }
+
+    /// Creates a component from its JSON form, which can use any content type of the game
+    pub fn from_json(mapping: &Mapping, json: &Value) -> Result<Self, MappingError> {
+        convert_local(mapping, decode_json(mapping, json)?, Component::from_java)
+    }
+
+    /// Text without styling, translated in the language of the game
+    pub fn get_string(&self) -> Result<String, MappingError> {
+        let mapping = self.mapping();
+
+        let string = component::get_string_0(mapping, self.jni_component.as_obj())?;
+        convert_local(mapping, string, String::from_java)
+    }
+
+    /// Styled spans of the text, translated in the language of the game
+    pub fn to_rich_text(&self) -> Result<RichText, MappingError> {
+        RichText::from_java(self.mapping(), self.jni_component.as_obj())
+    }
+
+    /// JSON form of the component, translatable components keep their key and arguments
+    pub fn to_json(&self) -> Result<Value, MappingError> {
+        encode_json(self.mapping(), self.jni_component.as_obj())
+    }
+}
+
+/// Serializes a component with `ComponentSerialization.CODEC` to a Gson tree and parses its string
+fn encode_json(mapping: &Mapping, component: &JObject) -> Result<Value, MappingError> {
+    let codec = component_serialization::get_codec(mapping)?;
+    let json_ops = json_ops(mapping)?;
+    let result = mapping.try_call_method(
+        CODEC,
+        &codec,
+        "encodeStart",
+        &[JValue::Object(&json_ops), JValue::Object(component)],
+    );
+    let env = mapping.try_get_env()?;
+    env.delete_local_ref(codec)?;
+    env.delete_local_ref(json_ops)?;
+    let result = result?.l()?;
+
+    let json = get_or_throw(mapping, &result);
+    env.delete_local_ref(result)?;
+    let string = convert_local(mapping, json?, |mapping, json| {
+        let string = mapping
+            .try_call_method(JSON_ELEMENT, json, "toString", &[])?
+            .l()?;
+        convert_local(mapping, string, String::from_java)
+    })?;
+
+    serde_json::from_str(&string).map_err(|e| invalid_component(e.to_string()))
+}
+
+/// Parses the JSON form of a component with Gson and decodes it with `ComponentSerialization.CODEC`
+fn decode_json<'a>(mapping: &'a Mapping, json: &Value) -> Result<JObject<'a>, MappingError> {
+    let string = json.to_string().to_java(mapping)?;
+    let json =
+        mapping.try_call_static_method(JSON_PARSER, "parseString", &[JValue::Object(&string)]);
+    mapping.try_get_env()?.delete_local_ref(string)?;
+    let json = json?.l()?;
+
+    let codec = component_serialization::get_codec(mapping)?;
+    let json_ops = json_ops(mapping)?;
+    let result = mapping.try_call_method(
+        CODEC,
+        &codec,
+        "parse",
+        &[JValue::Object(&json_ops), JValue::Object(&json)],
+    );
+    let env = mapping.try_get_env()?;
+    env.delete_local_ref(codec)?;
+    env.delete_local_ref(json_ops)?;
+    env.delete_local_ref(json)?;
+    let result = result?.l()?;
+
+    let component = get_or_throw(mapping, &result);
+    env.delete_local_ref(result)?;
+    component
+}
+
+fn json_ops<'a>(mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+    Ok(mapping
+        .try_get_static_field(
+            JSON_OPS,
+            "INSTANCE",
+            FieldType::Class("com/mojang/serialization/JsonOps"),
+        )?
+        .l()?)
+}
+
+/// Returns the value of a `DataResult`, an error is thrown as an `IllegalStateException` with its message
+fn get_or_throw<'a>(mapping: &'a Mapping, result: &JObject) -> Result<JObject<'a>, MappingError> {
+    Ok(mapping
+        .try_call_method(DATA_RESULT, result, "getOrThrow", &[])?
+        .l()?)
+}
+
+fn invalid_component(message: String) -> MappingError {
+    MappingError::InvalidValue {
+        class: MinecraftClassType::Component.get_name().to_string(),
+        message,
+    }
+}
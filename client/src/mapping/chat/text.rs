@@ -0,0 +1,485 @@
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+/// Text made of styled spans, the flattened form of a Minecraft `Component`
+///
+/// The JSON form is the text component format of the game, e.g. `{"text": "Hi", "bold": true}`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RichText {
+    pub spans: Vec<Span>,
+}
+
+/// Text sharing one style
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+/// Style of a span, `None` values are inherited from the parent component
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Style {
+    pub color: Option<TextColor>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub obfuscated: Option<bool>,
+    /// Written as `click_event`, the key used since 1.21.5
+    pub click_event: Option<ClickEvent>,
+    /// Text inserted in the chat input when the span is shift-clicked
+    pub insertion: Option<String>,
+}
+
+/// RGB colour of a span
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TextColor(pub u32);
+
+/// Action run when a span is clicked in the chat
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClickEvent {
+    OpenUrl(String),
+    OpenFile(String),
+    RunCommand(String),
+    SuggestCommand(String),
+    ChangePage(i32),
+    CopyToClipboard(String),
+}
+
+/// Colours of the `ChatFormatting` codes, serialized by name
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("black", 0x000000),
+    ("dark_blue", 0x0000aa),
+    ("dark_green", 0x00aa00),
+    ("dark_aqua", 0x00aaaa),
+    ("dark_red", 0xaa0000),
+    ("dark_purple", 0xaa00aa),
+    ("gold", 0xffaa00),
+    ("gray", 0xaaaaaa),
+    ("dark_gray", 0x555555),
+    ("blue", 0x5555ff),
+    ("green", 0x55ff55),
+    ("aqua", 0x55ffff),
+    ("red", 0xff5555),
+    ("light_purple", 0xff55ff),
+    ("yellow", 0xffff55),
+    ("white", 0xffffff),
+];
+
+const STYLE_FLAGS: &[&str] = &[
+    "bold",
+    "italic",
+    "underlined",
+    "strikethrough",
+    "obfuscated",
+];
+
+impl RichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Unstyled text
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::new().with(text, Style::default())
+    }
+
+    /// Appends a span, e.g. `RichText::plain("Fly: ").with("on", Style::color(TextColor::GREEN))`
+    pub fn with(mut self, text: impl Into<String>, style: Style) -> Self {
+        self.spans.push(Span {
+            text: text.into(),
+            style,
+        });
+        self
+    }
+
+    /// Reads the JSON form of a component, with the styles of the parents applied to every span
+    ///
+    /// Translatable components can't be translated outside the game, their fallback or their key is used.
+    /// Click events are read in the format of 1.21.5 (`click_event`) and of older versions (`clickEvent`).
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let mut text = RichText::new();
+        text.append_json(value, &Style::default())?;
+        Ok(text)
+    }
+
+    /// Writes the JSON form of the text, one child component per span
+    ///
+    /// Click events are written in the format of 1.21.5 (`click_event`), which older versions ignore.
+    pub fn to_json(&self) -> Value {
+        match self.spans.as_slice() {
+            [] => json!({"text": ""}),
+            [span] => span.to_json(),
+            spans => json!({
+                "text": "",
+                "extra": spans.iter().map(Span::to_json).collect::<Vec<_>>(),
+            }),
+        }
+    }
+
+    fn append_json(&mut self, value: &Value, parent: &Style) -> Result<(), String> {
+        match value {
+            Value::String(text) => self.push(text, parent),
+            // The first component is the parent of the next ones
+            Value::Array(components) => {
+                let (first, siblings) = components.split_first().ok_or("empty component list")?;
+                self.append_json(first, parent)?;
+                let style = Style::from_json(first)?.inherit(parent);
+                for sibling in siblings {
+                    self.append_json(sibling, &style)?;
+                }
+            }
+            Value::Object(component) => {
+                let style = Style::from_json(value)?.inherit(parent);
+                self.push(&content_text(component)?, &style);
+                if let Some(extra) = component.get("extra") {
+                    let extra = extra.as_array().ok_or("extra must be a list")?;
+                    for child in extra {
+                        self.append_json(child, &style)?;
+                    }
+                }
+            }
+            value => return Err(format!("invalid component {}", value)),
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, text: &str, style: &Style) {
+        if !text.is_empty() {
+            self.spans.push(Span {
+                text: text.to_string(),
+                style: style.clone(),
+            });
+        }
+    }
+}
+
+/// Formats the text without styling
+impl fmt::Display for RichText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.spans
+            .iter()
+            .try_for_each(|span| f.write_str(&span.text))
+    }
+}
+
+impl Span {
+    fn to_json(&self) -> Value {
+        let mut component = Map::new();
+        component.insert("text".to_string(), Value::from(self.text.as_str()));
+        self.style.write_json(&mut component);
+        Value::Object(component)
+    }
+}
+
+/// Text of the content of a component object, `translate` falls back to its `fallback` or its key
+fn content_text(component: &Map<String, Value>) -> Result<String, String> {
+    let string = |key: &str| component.get(key).and_then(Value::as_str);
+
+    if let Some(text) = component.get("text") {
+        return match text {
+            Value::String(text) => Ok(text.clone()),
+            // Numbers and booleans are accepted as text by the game
+            Value::Number(_) | Value::Bool(_) => Ok(text.to_string()),
+            _ => Err(format!("invalid text {}", text)),
+        };
+    }
+    if let Some(key) = string("translate") {
+        return Ok(string("fallback").unwrap_or(key).to_string());
+    }
+    if let Some(key) = string("keybind") {
+        return Ok(key.to_string());
+    }
+    if let Some(selector) = string("selector") {
+        return Ok(selector.to_string());
+    }
+    // Scores and NBT values are resolved by the server
+    if component.contains_key("score") || component.contains_key("nbt") {
+        return Ok(String::new());
+    }
+    if component.contains_key("extra") {
+        return Ok(String::new());
+    }
+    Err(format!(
+        "component without content {}",
+        Value::Object(component.clone())
+    ))
+}
+
+impl Style {
+    /// Style with only a colour
+    pub fn color(color: TextColor) -> Self {
+        Style {
+            color: Some(color),
+            ..Style::default()
+        }
+    }
+
+    /// Fills the unset values with the ones of `parent`
+    pub fn inherit(self, parent: &Style) -> Style {
+        Style {
+            color: self.color.or(parent.color),
+            bold: self.bold.or(parent.bold),
+            italic: self.italic.or(parent.italic),
+            underlined: self.underlined.or(parent.underlined),
+            strikethrough: self.strikethrough.or(parent.strikethrough),
+            obfuscated: self.obfuscated.or(parent.obfuscated),
+            click_event: self.click_event.or_else(|| parent.click_event.clone()),
+            insertion: self.insertion.or_else(|| parent.insertion.clone()),
+        }
+    }
+
+    /// Reads the style of a component, strings have none and lists have the style of their first component
+    fn from_json(value: &Value) -> Result<Style, String> {
+        let component = match value {
+            Value::Object(component) => component,
+            Value::Array(components) => {
+                return components
+                    .first()
+                    .map_or(Ok(Style::default()), Style::from_json)
+            }
+            _ => return Ok(Style::default()),
+        };
+
+        let mut style = Style::default();
+        if let Some(color) = component.get("color") {
+            let name = color.as_str().ok_or("color must be a string")?;
+            style.color =
+                Some(TextColor::parse(name).ok_or_else(|| format!("invalid color {}", name))?);
+        }
+        let flags = [
+            &mut style.bold,
+            &mut style.italic,
+            &mut style.underlined,
+            &mut style.strikethrough,
+            &mut style.obfuscated,
+        ];
+        for (key, flag) in STYLE_FLAGS.iter().zip(flags) {
+            if let Some(value) = component.get(*key) {
+                *flag = Some(
+                    value
+                        .as_bool()
+                        .ok_or_else(|| format!("{} must be a boolean", key))?,
+                );
+            }
+        }
+        if let Some(event) = component.get("click_event") {
+            style.click_event = Some(ClickEvent::from_json(event, false)?);
+        } else if let Some(event) = component.get("clickEvent") {
+            style.click_event = Some(ClickEvent::from_json(event, true)?);
+        }
+        if let Some(insertion) = component.get("insertion") {
+            let insertion = insertion.as_str().ok_or("insertion must be a string")?;
+            style.insertion = Some(insertion.to_string());
+        }
+        Ok(style)
+    }
+
+    fn write_json(&self, component: &mut Map<String, Value>) {
+        if let Some(color) = self.color {
+            component.insert("color".to_string(), Value::from(color.to_string()));
+        }
+        let flags = [
+            self.bold,
+            self.italic,
+            self.underlined,
+            self.strikethrough,
+            self.obfuscated,
+        ];
+        for (key, flag) in STYLE_FLAGS.iter().zip(flags) {
+            if let Some(flag) = flag {
+                component.insert(key.to_string(), Value::from(flag));
+            }
+        }
+        if let Some(event) = &self.click_event {
+            component.insert("click_event".to_string(), event.to_json());
+        }
+        if let Some(insertion) = &self.insertion {
+            component.insert("insertion".to_string(), Value::from(insertion.as_str()));
+        }
+    }
+}
+
+impl TextColor {
+    pub const BLACK: Self = Self(0x000000);
+    pub const DARK_RED: Self = Self(0xaa0000);
+    pub const GOLD: Self = Self(0xffaa00);
+    pub const GRAY: Self = Self(0xaaaaaa);
+    pub const BLUE: Self = Self(0x5555ff);
+    pub const GREEN: Self = Self(0x55ff55);
+    pub const AQUA: Self = Self(0x55ffff);
+    pub const RED: Self = Self(0xff5555);
+    pub const YELLOW: Self = Self(0xffff55);
+    pub const WHITE: Self = Self(0xffffff);
+
+    /// Parses a colour name like `red` or a hex colour like `#ff5555`
+    pub fn parse(value: &str) -> Option<Self> {
+        if let Some(hex) = value.strip_prefix('#') {
+            return u32::from_str_radix(hex, 16)
+                .ok()
+                .filter(|rgb| hex.len() == 6 && *rgb <= 0xffffff)
+                .map(TextColor);
+        }
+        NAMED_COLORS
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, rgb)| TextColor(*rgb))
+    }
+
+    /// Name of the formatting code with this colour
+    pub fn name(&self) -> Option<&'static str> {
+        NAMED_COLORS
+            .iter()
+            .find(|(_, rgb)| *rgb == self.0)
+            .map(|(name, _)| *name)
+    }
+}
+
+/// Formats like `TextColor.serialize()`, the name of the colour or its hex value
+impl fmt::Display for TextColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "#{:06x}", self.0),
+        }
+    }
+}
+
+impl ClickEvent {
+    pub fn action(&self) -> &'static str {
+        match self {
+            ClickEvent::OpenUrl(_) => "open_url",
+            ClickEvent::OpenFile(_) => "open_file",
+            ClickEvent::RunCommand(_) => "run_command",
+            ClickEvent::SuggestCommand(_) => "suggest_command",
+            ClickEvent::ChangePage(_) => "change_page",
+            ClickEvent::CopyToClipboard(_) => "copy_to_clipboard",
+        }
+    }
+
+    /// Key of the value since 1.21.5, older versions use `value` for every action
+    fn value_key(action: &str) -> Option<&'static str> {
+        match action {
+            "open_url" => Some("url"),
+            "open_file" => Some("path"),
+            "run_command" | "suggest_command" => Some("command"),
+            "change_page" => Some("page"),
+            "copy_to_clipboard" => Some("value"),
+            _ => None,
+        }
+    }
+
+    fn from_json(value: &Value, legacy: bool) -> Result<Self, String> {
+        let action = value
+            .get("action")
+            .and_then(Value::as_str)
+            .ok_or("click event without action")?;
+        let key = Self::value_key(action)
+            .ok_or_else(|| format!("unknown click event action {}", action))?;
+        let key = if legacy { "value" } else { key };
+        let value = value
+            .get(key)
+            .ok_or_else(|| format!("click event {} without {}", action, key))?;
+
+        let string = || {
+            value
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| format!("{} of click event {} must be a string", key, action))
+        };
+        Ok(match action {
+            "open_url" => ClickEvent::OpenUrl(string()?),
+            "open_file" => ClickEvent::OpenFile(string()?),
+            "run_command" => ClickEvent::RunCommand(string()?),
+            "suggest_command" => ClickEvent::SuggestCommand(string()?),
+            "change_page" => {
+                // Legacy pages are strings
+                let page = match value {
+                    Value::String(page) => page.parse().ok(),
+                    page => page.as_i64().and_then(|page| i32::try_from(page).ok()),
+                };
+                ClickEvent::ChangePage(page.ok_or_else(|| format!("invalid page {}", value))?)
+            }
+            _ => ClickEvent::CopyToClipboard(string()?),
+        })
+    }
+
+    fn to_json(&self) -> Value {
+        let value = match self {
+            ClickEvent::ChangePage(page) => Value::from(*page),
+            ClickEvent::OpenUrl(value)
+            | ClickEvent::OpenFile(value)
+            | ClickEvent::RunCommand(value)
+            | ClickEvent::SuggestCommand(value)
+            | ClickEvent::CopyToClipboard(value) => Value::from(value.as_str()),
+        };
+        let mut event = Map::new();
+        event.insert("action".to_string(), Value::from(self.action()));
+        event.insert(Self::value_key(self.action()).unwrap().to_string(), value);
+        Value::Object(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json() {
+        let value = json!([
+            {"text": "Hello ", "color": "gold", "bold": true},
+            {"text": "world", "bold": false, "clickEvent": {"action": "run_command", "value": "/spawn"}},
+            {"translate": "chat.unknown", "extra": ["!", {"text": "?", "color": "#123abc"}]}
+        ]);
+        let text = RichText::from_json(&value).unwrap();
+
+        assert_eq!(text.to_string(), "Hello worldchat.unknown!?");
+        let styles: Vec<_> = text.spans.iter().map(|span| &span.style).collect();
+        assert_eq!(styles[0].color, Some(TextColor::GOLD));
+        assert_eq!(styles[0].bold, Some(true));
+        // Siblings inherit the style of the first component
+        assert_eq!(styles[1].color, Some(TextColor::GOLD));
+        assert_eq!(styles[1].bold, Some(false));
+        assert_eq!(
+            styles[1].click_event,
+            Some(ClickEvent::RunCommand("/spawn".to_string()))
+        );
+        assert_eq!(styles[2].click_event, None);
+        assert_eq!(styles[3].bold, Some(true));
+        assert_eq!(styles[4].color, Some(TextColor(0x123abc)));
+
+        assert!(RichText::from_json(&json!([])).is_err());
+        assert!(RichText::from_json(&json!({"text": "a", "color": "pink"})).is_err());
+        assert!(RichText::from_json(&json!({"bold": true})).is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let text = RichText::plain("Fly: ")
+            .with("on", Style::color(TextColor::GREEN))
+            .with(
+                "page",
+                Style {
+                    color: Some(TextColor(0x010203)),
+                    underlined: Some(true),
+                    click_event: Some(ClickEvent::ChangePage(3)),
+                    ..Style::default()
+                },
+            );
+        let value = text.to_json();
+
+        assert_eq!(value["extra"][1]["color"], "green");
+        assert_eq!(value["extra"][2]["color"], "#010203");
+        assert_eq!(
+            value["extra"][2]["click_event"],
+            json!({"action": "change_page", "page": 3})
+        );
+        assert_eq!(RichText::from_json(&value).unwrap(), text);
+        assert_eq!(RichText::plain("a").to_json(), json!({"text": "a"}));
+        assert_eq!(RichText::new().to_json(), json!({"text": ""}));
+        assert_eq!(
+            RichText::from_json(&json!({"text": ""})).unwrap(),
+            RichText::new()
+        );
+    }
+}
@@ -0,0 +1,742 @@
+pub use crate::mapping::bindings::REFERENCES;
+use crate::mapping::error::MappingError;
+use crate::mapping::java::enums::ENUM_REFERENCES;
+use crate::mapping::proguard::java_type_to_descriptor;
+use crate::mapping::{FieldType, Mapping, MinecraftClassType, CONSTRUCTOR};
+use jni::objects::{JClass, JObject, JObjectArray, JString};
+use jni::JNIEnv;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Class member used by the client, checked by [`Mapping::self_check`]
+pub enum Reference {
+    Method {
+        class: MinecraftClassType,
+        name: &'static str,
+        is_static: bool,
+    },
+    Field {
+        class: MinecraftClassType,
+        name: &'static str,
+        field_type: fn(&Mapping) -> FieldType<'_>,
+    },
+}
+
+impl Reference {
+    pub fn class(&self) -> MinecraftClassType {
+        match self {
+            Reference::Method { class, .. } | Reference::Field { class, .. } => *class,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Reference::Method { name, .. } | Reference::Field { name, .. } => name,
+        }
+    }
+}
+
+const fn method(class: &'static str, name: &'static str) -> Reference {
+    Reference::Method {
+        class: MinecraftClassType::unmapped(class),
+        name,
+        is_static: false,
+    }
+}
+
+const fn static_method(class: &'static str, name: &'static str) -> Reference {
+    Reference::Method {
+        class: MinecraftClassType::unmapped(class),
+        name,
+        is_static: true,
+    }
+}
+
+/// Members called by hand-written code instead of the generated bindings, checked with [`REFERENCES`]
+///
+/// They belong to the libraries of the game and the JDK, whose names are never obfuscated, so they are
+/// looked up through reflection. A test fails when a hand-written call names a member missing here.
+pub const MANUAL_REFERENCES: &[Reference] = &[
+    // chat/mod.rs
+    method("com/mojang/serialization/Codec", "encodeStart"),
+    method("com/mojang/serialization/Codec", "parse"),
+    method("com/mojang/serialization/DataResult", "getOrThrow"),
+    Reference::Field {
+        class: MinecraftClassType::unmapped("com/mojang/serialization/JsonOps"),
+        name: "INSTANCE",
+        field_type: |_| FieldType::Class("com/mojang/serialization/JsonOps"),
+    },
+    static_method("com/google/gson/JsonParser", "parseString"),
+    method("com/google/gson/JsonElement", "toString"),
+    // client.rs
+    static_method("org/lwjgl/glfw/GLFW", "glfwGetKey"),
+    // java/collection.rs
+    method("java/lang/Iterable", "iterator"),
+    method("java/util/Iterator", "hasNext"),
+    method("java/util/Iterator", "next"),
+    method("java/util/Collection", "size"),
+    method("java/util/Collection", "isEmpty"),
+    method("java/util/Collection", "contains"),
+    method("java/util/List", "get"),
+    method("java/util/Map", "get"),
+    method("java/util/Map", "containsKey"),
+    method("java/util/Map", "keySet"),
+    method("java/util/Map", "values"),
+    method("java/util/Map", "entrySet"),
+    method("java/util/Map$Entry", "getKey"),
+    method("java/util/Map$Entry", "getValue"),
+    // java/optional.rs, java/uuid.rs and java/enums.rs
+    method("java/util/Optional", "isPresent"),
+    method("java/util/Optional", "get"),
+    static_method("java/util/Optional", "empty"),
+    static_method("java/util/Optional", "of"),
+    method("java/util/UUID", "getMostSignificantBits"),
+    method("java/util/UUID", "getLeastSignificantBits"),
+    method("java/util/UUID", CONSTRUCTOR),
+    method("java/lang/Enum", "name"),
+    // java/boxed.rs
+    method("java/lang/Boolean", "booleanValue"),
+    static_method("java/lang/Boolean", "valueOf"),
+    method("java/lang/Byte", "byteValue"),
+    static_method("java/lang/Byte", "valueOf"),
+    method("java/lang/Character", "charValue"),
+    static_method("java/lang/Character", "valueOf"),
+    method("java/lang/Short", "shortValue"),
+    static_method("java/lang/Short", "valueOf"),
+    method("java/lang/Integer", "intValue"),
+    static_method("java/lang/Integer", "valueOf"),
+    method("java/lang/Long", "longValue"),
+    static_method("java/lang/Long", "valueOf"),
+    method("java/lang/Float", "floatValue"),
+    static_method("java/lang/Float", "valueOf"),
+    method("java/lang/Double", "doubleValue"),
+    static_method("java/lang/Double", "valueOf"),
+];
+
+/// Every reference checked by [`Mapping::self_check`]: the bindings, the hand-written calls and the enum constants
+pub fn references() -> impl Iterator<Item = &'static Reference> {
+    REFERENCES
+        .iter()
+        .chain(MANUAL_REFERENCES)
+        .chain(ENUM_REFERENCES.iter().copied().flatten())
+}
+
+#[derive(Debug)]
+pub enum CheckStatus {
+    Ok,
+    /// Missing from the mapping, or recorded with a type the client doesn't expect
+    Unmapped(MappingError),
+    /// Mapped, but the game has no class or member with the runtime name
+    Missing {
+        runtime_name: String,
+    },
+    /// The game has a member with the runtime name, but with other signatures
+    SignatureChanged {
+        expected: String,
+        found: Vec<String>,
+    },
+    /// The check itself failed
+    Error(MappingError),
+}
+
+/// Result of a class (`member` is `None`) or of a member overload
+#[derive(Debug)]
+pub struct CheckResult {
+    pub class: MinecraftClassType,
+    pub member: Option<String>,
+    pub status: CheckStatus,
+}
+
+#[derive(Debug, Default)]
+pub struct SelfCheckReport {
+    pub results: Vec<CheckResult>,
+}
+
+impl SelfCheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems().next().is_none()
+    }
+
+    pub fn problems(&self) -> impl Iterator<Item = &CheckResult> {
+        self.results
+            .iter()
+            .filter(|result| !matches!(result.status, CheckStatus::Ok))
+    }
+
+    fn push(&mut self, class: MinecraftClassType, member: Option<String>, status: CheckStatus) {
+        self.results.push(CheckResult {
+            class,
+            member,
+            status,
+        });
+    }
+}
+
+impl fmt::Display for SelfCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Mapping self-check: {} of {} references resolved",
+            self.results.len() - self.problems().count(),
+            self.results.len()
+        )?;
+
+        for result in self.problems() {
+            write!(f, "\n  {}", result.class)?;
+            if let Some(member) = &result.member {
+                write!(f, ".{}", member)?;
+            }
+
+            match &result.status {
+                CheckStatus::Ok => {}
+                CheckStatus::Unmapped(e) => write!(f, ": {}", e)?,
+                CheckStatus::Missing { runtime_name } => {
+                    write!(f, ": {} not found in the game", runtime_name)?
+                }
+                CheckStatus::SignatureChanged { expected, found } => write!(
+                    f,
+                    ": signature changed, expected {}, found {}",
+                    expected,
+                    found.join(", ")
+                )?,
+                CheckStatus::Error(e) => write!(f, ": check failed, {}", e)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Mapping {
+    /// Resolves every entry of [`references`] in the running JVM
+    ///
+    /// Resolved classes and members stay in the resolution cache.
+    pub fn self_check(&self) -> SelfCheckReport {
+        let mut report = SelfCheckReport::default();
+        let mut env = match self.try_get_env() {
+            Ok(env) => env,
+            Err(e) => {
+                report.push(MinecraftClassType::Minecraft, None, CheckStatus::Error(e));
+                return report;
+            }
+        };
+
+        let mut classes: Vec<MinecraftClassType> = Vec::new();
+        for reference in references() {
+            if !classes.contains(&reference.class()) {
+                classes.push(reference.class());
+            }
+        }
+
+        for class_type in classes {
+            if !class_type.is_mapped() {
+                self.check_unmapped(&mut env, &mut report, class_type);
+                continue;
+            }
+
+            let class = match self.try_get_class(class_type.get_name()) {
+                Ok(class) => class,
+                Err(e) => {
+                    report.push(class_type, None, CheckStatus::Unmapped(e));
+                    continue;
+                }
+            };
+            let jclass = match self.cache.class(&mut env, class_type, &class.name) {
+                Ok(jclass) => jclass,
+                Err(e) => {
+                    report.push(class_type, None, self.missing(&mut env, e, &class.name));
+                    continue;
+                }
+            };
+            report.push(class_type, None, CheckStatus::Ok);
+
+            let jclass = <&JClass>::from(jclass.as_obj());
+            for reference in references().filter(|reference| reference.class() == class_type) {
+                match reference {
+                    Reference::Method {
+                        name, is_static, ..
+                    } => self.check_method(
+                        &mut env,
+                        &mut report,
+                        class_type,
+                        jclass,
+                        name,
+                        *is_static,
+                    ),
+                    Reference::Field {
+                        name, field_type, ..
+                    } => self.check_field(
+                        &mut env,
+                        &mut report,
+                        class_type,
+                        jclass,
+                        name,
+                        field_type(self),
+                    ),
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Looks up the references of mapped classes in the mapping only, the part of
+    /// [`Mapping::self_check`] that doesn't need a JVM
+    ///
+    /// Returns the number of references missing from the mapping.
+    pub fn resolve_references(&self) -> usize {
+        references()
+            .filter(|reference| reference.class().is_mapped())
+            .filter(|reference| match reference {
+                Reference::Method { class, name, .. } => self.overloads(*class, name).is_empty(),
+                Reference::Field {
+                    class,
+                    name,
+                    field_type,
+                } => {
+                    self.get_field_signature(*class, name, &field_type(self))
+                        .is_err()
+                        || self.resolve_field(*class, name).is_err()
+                }
+            })
+            .count()
+    }
+
+    /// Checks the members of a class outside the mapping by their runtime names, read through reflection
+    fn check_unmapped(
+        &self,
+        env: &mut JNIEnv,
+        report: &mut SelfCheckReport,
+        class_type: MinecraftClassType,
+    ) {
+        let class = match self.class_ref(env, class_type) {
+            Ok(class) => class,
+            Err(MappingError::JavaException { .. }) => {
+                let runtime_name = class_type.get_name().to_string();
+                report.push(class_type, None, CheckStatus::Missing { runtime_name });
+                return;
+            }
+            Err(e) => {
+                report.push(class_type, None, CheckStatus::Error(e));
+                return;
+            }
+        };
+        report.push(class_type, None, CheckStatus::Ok);
+
+        for reference in references().filter(|reference| reference.class() == class_type) {
+            let found = match reference {
+                Reference::Method { name, .. } => class
+                    .find_methods(name)
+                    .is_some_and(|overloads| !overloads.is_empty()),
+                Reference::Field { name, .. } => class.find_field(name).is_some(),
+            };
+            let status = if found {
+                CheckStatus::Ok
+            } else {
+                CheckStatus::Missing {
+                    runtime_name: reference.name().to_string(),
+                }
+            };
+            report.push(class_type, Some(reference.name().to_string()), status);
+        }
+    }
+
+    fn check_method(
+        &self,
+        env: &mut JNIEnv,
+        report: &mut SelfCheckReport,
+        class_type: MinecraftClassType,
+        jclass: &JClass,
+        name: &str,
+        is_static: bool,
+    ) {
+        let class = self.get_class(class_type.get_name());
+        // Inherited methods are resolved like `try_call_method` does, through the mapped hierarchy
+        let overloads = self.overloads(class_type, name);
+        if overloads.is_empty() {
+            let error = MappingError::MissingMethod {
+                class: class_type.get_name().to_string(),
+                method: name.to_string(),
+            };
+            report.push(
+                class_type,
+                Some(name.to_string()),
+                CheckStatus::Unmapped(error),
+            );
+            return;
+        }
+
+        for method in &overloads {
+            let member = if overloads.len() == 1 {
+                name.to_string()
+            } else {
+                // Runtime descriptor, deobfuscating it would build the reverse index at startup
+                format!("{}{}", name, method.signature)
+            };
+
+            let resolved = if is_static {
+                self.cache
+                    .static_method(
+                        env,
+                        class_type,
+                        &class.name,
+                        name,
+                        &method.name,
+                        &method.signature,
+                    )
+                    .map(|_| ())
+            } else {
+                self.cache
+                    .method(
+                        env,
+                        class_type,
+                        &class.name,
+                        name,
+                        &method.name,
+                        &method.signature,
+                    )
+                    .map(|_| ())
+            };
+
+            let status = match resolved {
+                Ok(()) => CheckStatus::Ok,
+                Err(e) => {
+                    self.changed_or_missing(env, e, jclass, &method.name, &method.signature, true)
+                }
+            };
+            report.push(class_type, Some(member), status);
+        }
+    }
+
+    fn check_field(
+        &self,
+        env: &mut JNIEnv,
+        report: &mut SelfCheckReport,
+        class_type: MinecraftClassType,
+        jclass: &JClass,
+        name: &str,
+        field_type: FieldType,
+    ) {
+        let class = self.get_class(class_type.get_name());
+        let signature = match self.get_field_signature(class_type, name, &field_type) {
+            Ok(signature) => signature,
+            Err(e) => {
+                report.push(class_type, Some(name.to_string()), CheckStatus::Unmapped(e));
+                return;
+            }
+        };
+        let field = match self.resolve_field(class_type, name) {
+            Ok(field) => field,
+            Err(e) => {
+                report.push(class_type, Some(name.to_string()), CheckStatus::Unmapped(e));
+                return;
+            }
+        };
+
+        let status =
+            match self
+                .cache
+                .field(env, class_type, &class.name, name, &field.name, &signature)
+            {
+                Ok(_) => CheckStatus::Ok,
+                Err(e) => self.changed_or_missing(env, e, jclass, &field.name, &signature, false),
+            };
+        report.push(class_type, Some(name.to_string()), status);
+    }
+
+    /// Clears the exception of a failed class lookup
+    fn missing(
+        &self,
+        env: &mut JNIEnv,
+        error: jni::errors::Error,
+        runtime_name: &str,
+    ) -> CheckStatus {
+        match MappingError::from_jni(env, error) {
+            MappingError::JavaException { .. } => CheckStatus::Missing {
+                runtime_name: runtime_name.to_string(),
+            },
+            e => CheckStatus::Error(e),
+        }
+    }
+
+    /// Tells a missing member apart from one whose signature changed, using reflection
+    fn changed_or_missing(
+        &self,
+        env: &mut JNIEnv,
+        error: jni::errors::Error,
+        jclass: &JClass,
+        runtime_name: &str,
+        signature: &str,
+        is_method: bool,
+    ) -> CheckStatus {
+        if let status @ CheckStatus::Error(_) = self.missing(env, error, runtime_name) {
+            return status;
+        }
+
+        match declared_signatures(env, jclass, runtime_name, is_method) {
+            Ok(found) if found.is_empty() => CheckStatus::Missing {
+                runtime_name: runtime_name.to_string(),
+            },
+            Ok(found) => CheckStatus::SignatureChanged {
+                expected: self.deobfuscate_descriptor(signature),
+                found: found
+                    .iter()
+                    .map(|signature| self.deobfuscate_descriptor(signature))
+                    .collect(),
+            },
+            Err(e) => CheckStatus::Error(MappingError::from_jni(env, e)),
+        }
+    }
+}
+
+/// Returns the descriptors of the methods or fields named `name` in `class` and its superclasses
+fn declared_signatures(
+    env: &mut JNIEnv,
+    class: &JClass,
+    name: &str,
+    is_method: bool,
+) -> jni::errors::Result<Vec<String>> {
+    env.with_local_frame(64, |env| {
+        let mut signatures = Vec::new();
+        let mut current = env.new_local_ref(class)?;
+
+        // Constructors are named after their class and not inherited
+        let is_constructor = is_method && name == CONSTRUCTOR;
+
+        while !current.is_null() {
+            let (getter, array_type) = if is_constructor {
+                (
+                    "getDeclaredConstructors",
+                    "()[Ljava/lang/reflect/Constructor;",
+                )
+            } else if is_method {
+                ("getDeclaredMethods", "()[Ljava/lang/reflect/Method;")
+            } else {
+                ("getDeclaredFields", "()[Ljava/lang/reflect/Field;")
+            };
+            let members =
+                JObjectArray::from(env.call_method(&current, getter, array_type, &[])?.l()?);
+
+            for index in 0..env.get_array_length(&members)? {
+                let member = env.get_object_array_element(&members, index)?;
+                let member_name = env
+                    .call_method(&member, "getName", "()Ljava/lang/String;", &[])?
+                    .l()?;
+                let member_name: String = env.get_string(&JString::from(member_name))?.into();
+
+                if member_name == name || is_constructor {
+                    signatures.push(member_descriptor(env, &member, is_method)?);
+                }
+                env.delete_local_ref(member)?;
+            }
+
+            if is_constructor {
+                break;
+            }
+
+            current = env
+                .call_method(&current, "getSuperclass", "()Ljava/lang/Class;", &[])?
+                .l()?;
+        }
+
+        Ok(signatures)
+    })
+}
+
+pub(super) fn member_descriptor(
+    env: &mut JNIEnv,
+    member: &JObject,
+    is_method: bool,
+) -> jni::errors::Result<String> {
+    if !is_method {
+        let field_type = env
+            .call_method(member, "getType", "()Ljava/lang/Class;", &[])?
+            .l()?;
+        return class_descriptor(env, &field_type);
+    }
+
+    let parameters = JObjectArray::from(
+        env.call_method(member, "getParameterTypes", "()[Ljava/lang/Class;", &[])?
+            .l()?,
+    );
+    let mut descriptor = String::from("(");
+    for index in 0..env.get_array_length(&parameters)? {
+        let parameter = env.get_object_array_element(&parameters, index)?;
+        descriptor.push_str(&class_descriptor(env, &parameter)?);
+    }
+    descriptor.push(')');
+
+    if !env.is_instance_of(member, "java/lang/reflect/Method")? {
+        descriptor.push('V');
+        return Ok(descriptor);
+    }
+    let return_type = env
+        .call_method(member, "getReturnType", "()Ljava/lang/Class;", &[])?
+        .l()?;
+    descriptor.push_str(&class_descriptor(env, &return_type)?);
+    Ok(descriptor)
+}
+
+/// Converts a `java.lang.Class` to a descriptor, `Class.getName` already returns one for arrays
+pub(super) fn class_descriptor(env: &mut JNIEnv, class: &JObject) -> jni::errors::Result<String> {
+    let name = env
+        .call_method(class, "getName", "()Ljava/lang/String;", &[])?
+        .l()?;
+    let name: String = env.get_string(&JString::from(name))?.into();
+
+    Ok(if name.starts_with('[') {
+        name.replace('.', "/")
+    } else {
+        java_type_to_descriptor(&name, &HashMap::new())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sources calling members by name instead of through the generated bindings
+    const HAND_WRITTEN: &[(&str, &str)] = &[
+        ("client.rs", include_str!("../client.rs")),
+        ("mapping/chat/mod.rs", include_str!("chat/mod.rs")),
+        ("mapping/java/mod.rs", include_str!("java/mod.rs")),
+        ("mapping/java/boxed.rs", include_str!("java/boxed.rs")),
+        (
+            "mapping/java/collection.rs",
+            include_str!("java/collection.rs"),
+        ),
+        ("mapping/java/enums.rs", include_str!("java/enums.rs")),
+        ("mapping/java/optional.rs", include_str!("java/optional.rs")),
+        ("mapping/java/uuid.rs", include_str!("java/uuid.rs")),
+    ];
+
+    /// Functions and macros taking a member name, directly or through a helper
+    const CALLS: &[&str] = &[
+        "call_method(",
+        "call_static_method(",
+        "call_object(",
+        "get_static_field(",
+        "get_field(",
+        "set_field(",
+        "new_object(",
+        "from_call(",
+        "view_iterator(",
+        "contains(",
+        "boxed_primitive!(",
+    ];
+
+    /// String literals shaped like a Java member name in the arguments of [`CALLS`], outside comments and tests
+    fn member_names(source: &str) -> Vec<&str> {
+        let code = source.split("#[cfg(test)]\nmod tests").next().unwrap();
+        let mut names = Vec::new();
+
+        for line_start in code.lines().filter(|line| !line.trim().starts_with("//")) {
+            let offset = line_start.as_ptr() as usize - code.as_ptr() as usize;
+            for call in CALLS {
+                for (index, _) in line_start.match_indices(call) {
+                    names.extend(literals(&code[offset + index + call.len()..]));
+                }
+            }
+        }
+
+        names.retain(|literal| {
+            // Single letters are primitive descriptors
+            literal.len() > 1
+                && literal.starts_with(|ch: char| ch.is_ascii_alphabetic())
+                && literal
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        });
+        names
+    }
+
+    /// String literals up to the parenthesis closing the arguments that start `code`
+    fn literals(code: &str) -> Vec<&str> {
+        let mut literals = Vec::new();
+        let mut depth = 0;
+        let mut string_start = None;
+
+        for (index, ch) in code.char_indices() {
+            match (ch, string_start) {
+                ('"', Some(start)) => {
+                    literals.push(&code[start..index]);
+                    string_start = None;
+                }
+                (_, Some(_)) => {}
+                ('"', None) => string_start = Some(index + 1),
+                ('(', None) => depth += 1,
+                (')', None) if depth == 0 => break,
+                (')', None) => depth -= 1,
+                _ => {}
+            }
+        }
+        literals
+    }
+
+    #[test]
+    fn test_hand_written_calls_are_listed() {
+        let listed: Vec<&str> = references().map(Reference::name).collect();
+
+        for (file, source) in HAND_WRITTEN {
+            let missing: Vec<&str> = member_names(source)
+                .into_iter()
+                .filter(|name| !listed.contains(name))
+                .collect();
+            assert!(
+                missing.is_empty(),
+                "{} calls {:?}, add them to MANUAL_REFERENCES",
+                file,
+                missing
+            );
+        }
+    }
+
+    #[test]
+    fn test_member_names() {
+        let source = r#"
+            // call_method(mapping, object, "commented", "()V", &[])
+            check_not_null(object, "String")?;
+            let next = call_method(mapping, object, "hasNext", "()Z", &[])?;
+            boxed_primitive!(bool, "java/lang/Boolean", "booleanValue", "Z", z);
+            mapping.try_call_static_method(
+                GLFW,
+                "glfwGetKey",
+                &[JValue::Long(window), JValue::Int(key)],
+            )?;
+            info!("Client {}", "initialized");
+        "#;
+        assert_eq!(
+            member_names(source),
+            ["hasNext", "booleanValue", "glfwGetKey"]
+        );
+    }
+
+    #[test]
+    fn test_report_display() {
+        let mut report = SelfCheckReport::default();
+        report.push(MinecraftClassType::Entity, None, CheckStatus::Ok);
+        report.push(
+            MinecraftClassType::Entity,
+            Some("position".to_string()),
+            CheckStatus::SignatureChanged {
+                expected: "()Lnet/minecraft/world/phys/Vec3;".to_string(),
+                found: vec!["(F)Lnet/minecraft/world/phys/Vec3;".to_string()],
+            },
+        );
+        report.push(
+            MinecraftClassType::Vec3,
+            None,
+            CheckStatus::Missing {
+                runtime_name: "fby".to_string(),
+            },
+        );
+
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.to_string(),
+            "Mapping self-check: 1 of 3 references resolved
+  net/minecraft/world/entity/Entity.position: signature changed, expected ()Lnet/minecraft/world/phys/Vec3;, found (F)Lnet/minecraft/world/phys/Vec3;
+  net/minecraft/world/phys/Vec3: fby not found in the game"
+        );
+    }
+}
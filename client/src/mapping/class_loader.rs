@@ -0,0 +1,185 @@
+use crate::client::DarkClient;
+use crate::mapping::{Mapping, MinecraftClassType};
+use jni::objects::{JObject, JObjectArray, JString, JValue};
+use jni::JNIEnv;
+use log::{info, warn};
+
+/// Name Minecraft gives to the thread running the game, its context class loader sees the mod classes
+const GAME_THREAD: &str = "Render thread";
+
+impl Mapping {
+    /// Captures the class loader of the `Minecraft` class, every mapped and unmapped class is loaded through it
+    ///
+    /// Without it the classes are loaded with the context class loader of the game thread.
+    pub fn capture_class_loader(&self) {
+        let Some(runtime_name) = self.runtime_name(MinecraftClassType::Minecraft.get_name()) else {
+            return;
+        };
+        let mut env = match DarkClient::instance().get_env() {
+            Ok(env) => env,
+            Err(e) => {
+                warn!("Unable to capture the game class loader: {}", e);
+                return;
+            }
+        };
+
+        let captured = self
+            .cache
+            .capture_class_loader(&mut env, runtime_name)
+            .and_then(|class_loader| class_loader_name(&mut env, class_loader.as_obj()));
+        match captured {
+            Ok(name) => info!("Loading the game classes with {}", name),
+            Err(e) => {
+                let e = self.java_error(&mut env, e);
+                warn!("Unable to capture the game class loader: {}", e);
+            }
+        }
+    }
+}
+
+/// Returns the context class loader of the game thread, or of the current thread if it isn't found
+///
+/// Threads attached from native code get the system class loader, which can't see the classes of mods.
+pub fn context_class_loader<'local>(
+    env: &mut JNIEnv<'local>,
+) -> jni::errors::Result<JObject<'local>> {
+    let game_thread = env.with_local_frame_returning_local(16, |env| {
+        let threads = env
+            .call_static_method(
+                "java/lang/Thread",
+                "getAllStackTraces",
+                "()Ljava/util/Map;",
+                &[],
+            )?
+            .l()?;
+        let threads = env
+            .call_method(&threads, "keySet", "()Ljava/util/Set;", &[])?
+            .l()?;
+        let threads = JObjectArray::from(
+            env.call_method(&threads, "toArray", "()[Ljava/lang/Object;", &[])?
+                .l()?,
+        );
+
+        for index in 0..env.get_array_length(&threads)? {
+            let thread = env.get_object_array_element(&threads, index)?;
+            let name = env
+                .call_method(&thread, "getName", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let name: String = env.get_string(&JString::from(name))?.into();
+            if name == GAME_THREAD {
+                return Ok(thread);
+            }
+            env.delete_local_ref(thread)?;
+        }
+
+        env.call_static_method(
+            "java/lang/Thread",
+            "currentThread",
+            "()Ljava/lang/Thread;",
+            &[],
+        )?
+        .l()
+    })?;
+
+    let class_loader = env
+        .call_method(
+            &game_thread,
+            "getContextClassLoader",
+            "()Ljava/lang/ClassLoader;",
+            &[],
+        )?
+        .l()?;
+    env.delete_local_ref(game_thread)?;
+    if !class_loader.is_null() {
+        return Ok(class_loader);
+    }
+
+    env.call_static_method(
+        "java/lang/ClassLoader",
+        "getSystemClassLoader",
+        "()Ljava/lang/ClassLoader;",
+        &[],
+    )?
+    .l()
+}
+
+/// Loads a class by internal name with `Class.forName`, without initializing it
+pub fn load_class<'local>(
+    env: &mut JNIEnv<'local>,
+    class_loader: &JObject,
+    name: &str,
+) -> jni::errors::Result<JObject<'local>> {
+    let java_name = env.new_string(name.replace('/', "."))?;
+    let class = env.call_static_method(
+        "java/lang/Class",
+        "forName",
+        "(Ljava/lang/String;ZLjava/lang/ClassLoader;)Ljava/lang/Class;",
+        &[
+            JValue::Object(&java_name),
+            JValue::Bool(0),
+            JValue::Object(class_loader),
+        ],
+    );
+    // Deleting a local reference is allowed with the ClassNotFoundException pending
+    env.delete_local_ref(java_name)?;
+    class?.l()
+}
+
+/// Returns the class loader that defined the game class `probe`, found through the context class loader
+///
+/// Launchers and mod loaders (Fabric's Knot, Forge's ModLauncher) load the game in their own class
+/// loader, which `find_class` from a native thread doesn't use. Falls back to the context class loader
+/// if the probe can't be loaded.
+pub fn game_class_loader<'local>(
+    env: &mut JNIEnv<'local>,
+    probe: &str,
+) -> jni::errors::Result<JObject<'local>> {
+    let context_class_loader = context_class_loader(env)?;
+    let class = match load_class(env, &context_class_loader, probe) {
+        Ok(class) => class,
+        Err(_) => {
+            // Class.forName leaves a ClassNotFoundException pending
+            env.exception_clear()?;
+            return Ok(context_class_loader);
+        }
+    };
+
+    let class_loader = env
+        .call_method(&class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+        .l()?;
+    env.delete_local_ref(class)?;
+    if class_loader.is_null() {
+        Ok(context_class_loader)
+    } else {
+        env.delete_local_ref(context_class_loader)?;
+        Ok(class_loader)
+    }
+}
+
+/// Loads a class through the context class loader of the game thread, `None` if it doesn't exist
+pub fn find_game_class<'local>(
+    env: &mut JNIEnv<'local>,
+    name: &str,
+) -> jni::errors::Result<Option<JObject<'local>>> {
+    let class_loader = context_class_loader(env)?;
+    let class = load_class(env, &class_loader, name);
+    env.delete_local_ref(class_loader)?;
+    match class {
+        Ok(class) => Ok(Some(class)),
+        Err(jni::errors::Error::JavaException) => {
+            env.exception_clear()?;
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Returns the Java class name of a class loader, e.g. `net.fabricmc.loader.impl.launch.knot.KnotClassLoader`
+pub fn class_loader_name(env: &mut JNIEnv, class_loader: &JObject) -> jni::errors::Result<String> {
+    let class = env.get_object_class(class_loader)?;
+    let name = env
+        .call_method(&class, "getName", "()Ljava/lang/String;", &[])?
+        .l()?;
+    let value = env.get_string(&JString::from(name))?.into();
+    Ok(value)
+}
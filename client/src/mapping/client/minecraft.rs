@@ -1,11 +1,17 @@
+use crate::mapping::bindings;
 use crate::mapping::client::window::Window;
 use crate::mapping::client::world::World;
 use crate::mapping::entity::player::LocalPlayer;
-use crate::mapping::{Mapping, MinecraftClassType};
+use crate::mapping::loader::MappingLoadError;
+use crate::mapping::version::detect_game_version;
+use crate::mapping::Mapping;
+use crate::LogExpect;
 use jni::objects::GlobalRef;
-use log::error;
+use log::{error, info, warn};
 use std::sync::{Arc, OnceLock};
 
+static INSTANCE: OnceLock<Result<Arc<Minecraft>, MappingLoadError>> = OnceLock::new();
+
 #[derive(Debug)]
 pub struct Minecraft {
     pub jni_ref: GlobalRef,
@@ -17,35 +23,54 @@ pub struct Minecraft {
 
 impl Minecraft {
     pub fn instance() -> &'static Minecraft {
-        static INSTANCE: OnceLock<Arc<Minecraft>> = OnceLock::new();
+        Self::try_instance().log_expect("Minecraft is not available")
+    }
+
+    /// Returns the instance, or why no mapping could be loaded for the running game
+    pub fn try_instance() -> Result<&'static Minecraft, &'static MappingLoadError> {
+        INSTANCE
+            .get_or_init(|| unsafe { Minecraft::new().map(Arc::new) })
+            .as_ref()
+            .map(Arc::as_ref)
+    }
 
-        INSTANCE.get_or_init(|| unsafe { Arc::new(Minecraft::new()) })
+    /// Returns the instance only if it was already created, without initializing it
+    pub fn get_initialized() -> Option<&'static Minecraft> {
+        INSTANCE.get()?.as_ref().ok().map(Arc::as_ref)
     }
 
-    unsafe fn new() -> Minecraft {
-        let mapping = Mapping::new();
-        let minecraft = mapping
-            .call_static_method(MinecraftClassType::Minecraft, "getInstance", &[])
-            .l()
-            .unwrap();
+    unsafe fn new() -> Result<Minecraft, MappingLoadError> {
+        let version = match detect_game_version() {
+            Ok(version) => {
+                info!("Detected Minecraft {}", version);
+                Some(version)
+            }
+            Err(e) => {
+                warn!("Unable to detect the game version: {}", e);
+                None
+            }
+        };
+
+        let mapping = Mapping::new(version.as_deref())?;
+        let minecraft = bindings::minecraft::get_instance(&mapping)?;
 
         if minecraft.is_null() {
             error!("Minecraft is null")
         }
 
-        let minecraft = mapping.new_global_ref(minecraft);
+        let minecraft = mapping.try_new_global_ref(minecraft)?;
 
-        let player = LocalPlayer::new(&minecraft, &mapping);
-        let world = World::new(&minecraft, &mapping);
-        let window = Window::new(&minecraft, &mapping);
+        let player = LocalPlayer::new(&minecraft, &mapping)?;
+        let world = World::new(&minecraft, &mapping)?;
+        let window = Window::new(&minecraft, &mapping)?;
 
-        Minecraft {
+        Ok(Minecraft {
             jni_ref: minecraft,
             mapping,
             player,
             world,
             window,
-        }
+        })
     }
 
     pub fn get_mapping(&self) -> &Mapping {
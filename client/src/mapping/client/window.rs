@@ -1,4 +1,6 @@
-use crate::mapping::{GameContext, Mapping, MinecraftClassType};
+use crate::mapping::bindings::{minecraft, window};
+use crate::mapping::error::MappingError;
+use crate::mapping::{GameContext, Mapping};
 use jni::objects::GlobalRef;
 use jni::sys::jlong;
 
@@ -10,33 +12,15 @@ pub struct Window {
 impl GameContext for Window {}
 
 impl Window {
-    pub fn new(minecraft: &GlobalRef, mapping: &Mapping) -> Window {
-        let window_obj = mapping
-            .call_method(
-                MinecraftClassType::Minecraft,
-                minecraft.as_obj(),
-                "getWindow",
-                &[],
-            )
-            .l()
-            .unwrap();
+    pub fn new(minecraft: &GlobalRef, mapping: &Mapping) -> Result<Window, MappingError> {
+        let window_obj = minecraft::get_window(mapping, minecraft.as_obj())?;
 
-        Window {
-            jni_ref: mapping.new_global_ref(window_obj),
-        }
+        Ok(Window {
+            jni_ref: mapping.try_new_global_ref(window_obj)?,
+        })
     }
 
-    pub fn get_window(&self) -> jlong {
-        let mapping = self.mapping();
-
-        mapping
-            .call_method(
-                MinecraftClassType::Window,
-                self.jni_ref.as_obj(),
-                "getWindow",
-                &[],
-            )
-            .j()
-            .unwrap()
+    pub fn get_window(&self) -> Result<jlong, MappingError> {
+        window::get_window(self.mapping(), self.jni_ref.as_obj())
     }
 }
@@ -1,4 +1,6 @@
-use crate::mapping::{FieldType, GameContext, Mapping, MinecraftClassType};
+use crate::mapping::bindings::minecraft;
+use crate::mapping::error::MappingError;
+use crate::mapping::{GameContext, Mapping};
 use jni::objects::GlobalRef;
 
 #[derive(Debug)]
@@ -9,19 +11,11 @@ pub struct World {
 impl GameContext for World {}
 
 impl World {
-    pub fn new(minecraft: &GlobalRef, mapping: &Mapping) -> World {
-        let world_obj = mapping
-            .get_field(
-                MinecraftClassType::Minecraft,
-                minecraft.as_obj(),
-                "level",
-                FieldType::Object(MinecraftClassType::Level, mapping),
-            )
-            .l()
-            .unwrap();
+    pub fn new(minecraft: &GlobalRef, mapping: &Mapping) -> Result<World, MappingError> {
+        let world_obj = minecraft::get_level(mapping, minecraft.as_obj())?;
 
-        World {
-            jni_world: mapping.new_global_ref(world_obj),
-        }
+        Ok(World {
+            jni_world: mapping.try_new_global_ref(world_obj)?,
+        })
     }
 }
@@ -0,0 +1,249 @@
+pub use crate::mapping::jni_descriptor::remap_descriptor;
+use crate::mapping::proguard::java_type_to_descriptor;
+use crate::mapping::Mapping;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Names of the classes in the method and field descriptors of a mapping file
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DescriptorNames {
+    /// The runtime names of the classes, like the member names
+    #[default]
+    Runtime,
+    /// The Mojang names, shareable between versions and namespaces
+    Mojang,
+}
+
+impl DescriptorNames {
+    pub fn is_runtime(&self) -> bool {
+        *self == DescriptorNames::Runtime
+    }
+}
+
+impl Mapping {
+    /// Rewrites descriptors stored with Mojang names to the runtime names of the classes
+    ///
+    /// Done when the mapping is loaded, lookups always compare runtime descriptors.
+    /// Classes missing from the mapping, like `java/lang/String`, are kept as they are.
+    pub fn resolve_descriptors(&mut self) {
+        if self.descriptors == DescriptorNames::Runtime {
+            return;
+        }
+
+        let runtime_names: HashMap<String, String> = self
+            .class_names()
+            .map(|(name, runtime_name)| (name.to_string(), runtime_name.to_string()))
+            .collect();
+        self.remap_descriptors(|name| runtime_names.get(name));
+        self.descriptors = DescriptorNames::Runtime;
+        self.reverse = OnceLock::new();
+    }
+
+    /// Rewrites the descriptors with the Mojang names of the classes, the inverse of [`Mapping::resolve_descriptors`]
+    pub fn deobfuscate_descriptors(&mut self) {
+        if self.descriptors == DescriptorNames::Mojang {
+            return;
+        }
+
+        let mojang_names: HashMap<String, String> = self
+            .class_names()
+            .map(|(name, runtime_name)| (runtime_name.to_string(), name.to_string()))
+            .collect();
+        self.remap_descriptors(|name| mojang_names.get(name));
+        self.descriptors = DescriptorNames::Mojang;
+        self.reverse = OnceLock::new();
+    }
+
+    pub(super) fn remap_descriptors<'a, F>(&mut self, remap: F)
+    where
+        F: Fn(&str) -> Option<&'a String>,
+    {
+        self.unpack();
+        for class in self.classes.values_mut() {
+            for method in class.methods.values_mut().flatten() {
+                method.signature = remap_descriptor(&method.signature, &remap);
+            }
+            for field in class.fields.values_mut() {
+                if let Some(signature) = &field.signature {
+                    field.signature = Some(remap_descriptor(signature, &remap));
+                }
+            }
+        }
+    }
+}
+
+/// Converts a JNI field descriptor to its Java source form
+///
+/// # Example
+/// `[Lnet/minecraft/world/phys/Vec3;` -> `net.minecraft.world.phys.Vec3[]`
+pub fn descriptor_to_java_type(descriptor: &str) -> String {
+    let element_type = descriptor.trim_start_matches('[');
+    let array_depth = descriptor.len() - element_type.len();
+
+    let mut java_type = match element_type {
+        "V" => "void".to_string(),
+        "Z" => "boolean".to_string(),
+        "B" => "byte".to_string(),
+        "C" => "char".to_string(),
+        "S" => "short".to_string(),
+        "I" => "int".to_string(),
+        "J" => "long".to_string(),
+        "F" => "float".to_string(),
+        "D" => "double".to_string(),
+        class_type => class_type
+            .strip_prefix('L')
+            .and_then(|class_type| class_type.strip_suffix(';'))
+            .unwrap_or(class_type)
+            .replace('/', "."),
+    };
+
+    java_type.push_str(&"[]".repeat(array_depth));
+    java_type
+}
+
+/// A method name followed by the parameter types selecting one of its overloads
+///
+/// # Example
+/// `setPos(double,double,double)` or `setPos(Lnet/minecraft/world/phys/Vec3;)V`
+#[derive(Debug, PartialEq)]
+pub struct MethodSelector<'a> {
+    pub name: &'a str,
+    /// Parameter descriptors without the parentheses, e.g. `DDD`
+    pub parameters: String,
+    /// Only known when the selector is a descriptor
+    pub return_type: Option<&'a str>,
+}
+
+impl<'a> MethodSelector<'a> {
+    /// Parses a selector, `None` if `selector` is a plain method name
+    ///
+    /// Parameters are Java types separated by commas, or a descriptor when a return type follows them.
+    pub fn parse(selector: &'a str) -> Option<Self> {
+        let (name, rest) = selector.split_once('(')?;
+        let (parameters, return_type) = rest.split_once(')')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        if return_type.is_empty() {
+            let parameters = parameters
+                .split(',')
+                .map(str::trim)
+                .filter(|parameter| !parameter.is_empty())
+                .map(|parameter| {
+                    let parameter = match parameter.strip_suffix("...") {
+                        Some(element_type) => format!("{}[]", element_type),
+                        None => parameter.to_string(),
+                    };
+                    java_type_to_descriptor(&parameter, &HashMap::new())
+                })
+                .collect();
+            Some(MethodSelector {
+                name,
+                parameters,
+                return_type: None,
+            })
+        } else {
+            Some(MethodSelector {
+                name,
+                parameters: parameters.to_string(),
+                return_type: Some(return_type),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descriptor_to_java_type() {
+        assert_eq!(descriptor_to_java_type("D"), "double");
+        assert_eq!(descriptor_to_java_type("[[I"), "int[][]");
+        assert_eq!(
+            descriptor_to_java_type("[Lnet/minecraft/world/phys/Vec3;"),
+            "net.minecraft.world.phys.Vec3[]"
+        );
+    }
+
+    #[test]
+    fn test_resolve_descriptors() {
+        let mut mapping: Mapping = serde_json::from_str(
+            r#"{
+                "descriptors": "mojang",
+                "classes": {
+                    "net/minecraft/client/Minecraft": {
+                        "name": "fzz",
+                        "methods": {
+                            "getInstance": {"name": "R", "signature": "()Lnet/minecraft/client/Minecraft;"},
+                            "getVersionType": {"name": "d", "signature": "()Ljava/lang/String;"}
+                        },
+                        "fields": {
+                            "player": {"name": "t", "signature": "Lnet/minecraft/client/player/LocalPlayer;"}
+                        }
+                    },
+                    "net/minecraft/client/player/LocalPlayer": {"name": "gkv", "methods": {}, "fields": {}}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        mapping.resolve_descriptors();
+        let minecraft = mapping.get_class("net/minecraft/client/Minecraft");
+        assert_eq!(minecraft.get_method("getInstance").signature, "()Lfzz;");
+        assert_eq!(
+            minecraft.get_method("getVersionType").signature,
+            "()Ljava/lang/String;"
+        );
+        assert_eq!(
+            minecraft.get_field("player").signature.as_deref(),
+            Some("Lgkv;")
+        );
+
+        mapping.deobfuscate_descriptors();
+        let value = serde_json::to_value(&mapping).unwrap();
+        assert_eq!(value["descriptors"], "mojang");
+        assert_eq!(
+            value["classes"]["net/minecraft/client/Minecraft"]["fields"]["player"]["signature"],
+            "Lnet/minecraft/client/player/LocalPlayer;"
+        );
+    }
+
+    #[test]
+    fn test_method_selector() {
+        assert_eq!(MethodSelector::parse("setPos"), None);
+        assert_eq!(
+            MethodSelector::parse("setPos(double, double, double)"),
+            Some(MethodSelector {
+                name: "setPos",
+                parameters: "DDD".to_string(),
+                return_type: None,
+            })
+        );
+        assert_eq!(
+            MethodSelector::parse("setPos(net.minecraft.world.phys.Vec3)")
+                .unwrap()
+                .parameters,
+            "Lnet/minecraft/world/phys/Vec3;"
+        );
+        assert_eq!(
+            MethodSelector::parse("format(java.lang.String,java.lang.Object...)")
+                .unwrap()
+                .parameters,
+            "Ljava/lang/String;[Ljava/lang/Object;"
+        );
+        assert_eq!(
+            MethodSelector::parse("setPos(Lnet/minecraft/world/phys/Vec3;)V"),
+            Some(MethodSelector {
+                name: "setPos",
+                parameters: "Lnet/minecraft/world/phys/Vec3;".to_string(),
+                return_type: Some("V"),
+            })
+        );
+        assert_eq!(MethodSelector::parse("tick()").unwrap().parameters, "");
+    }
+}
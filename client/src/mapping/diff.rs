@@ -0,0 +1,304 @@
+use crate::mapping::check::{Reference, REFERENCES};
+use crate::mapping::{Mapping, MinecraftClass, MinecraftClassType};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// Change of a class or member between two mappings
+///
+/// Classes and members are matched by Mojang name and descriptors are compared with Mojang names,
+/// so the runtime names changing between game versions is not reported.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    /// The only overload of a method, or a field, has another descriptor
+    SignatureChanged {
+        from: String,
+        to: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub class: String,
+    /// Method name with its descriptor, or field name, `None` for the class itself
+    pub member: Option<String>,
+    pub change: Change,
+}
+
+/// Changes from one mapping to another, sorted by class
+#[derive(Debug, Default)]
+pub struct MappingDiff {
+    pub entries: Vec<DiffEntry>,
+}
+
+impl MappingDiff {
+    /// Keeps the changes to the classes and members used by the client, see [`REFERENCES`]
+    ///
+    /// A class is kept if it is predefined or has a referenced member.
+    pub fn referenced(self) -> MappingDiff {
+        let is_class_referenced = |class: &str| {
+            MinecraftClassType::ALL
+                .iter()
+                .any(|class_type| class_type.get_name() == class)
+                || REFERENCES
+                    .iter()
+                    .any(|reference| reference_of(reference).0 == class)
+        };
+        let is_member_referenced = |class: &str, member: &str| {
+            // Methods are reported with their descriptor
+            let name = member.split_once('(').map_or(member, |(name, _)| name);
+            REFERENCES
+                .iter()
+                .any(|reference| reference_of(reference) == (class, name))
+        };
+
+        MappingDiff {
+            entries: self
+                .entries
+                .into_iter()
+                .filter(|entry| match &entry.member {
+                    None => is_class_referenced(&entry.class),
+                    Some(member) => is_member_referenced(&entry.class, member),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn reference_of(reference: &Reference) -> (&'static str, &'static str) {
+    match reference {
+        Reference::Method { class, name, .. } | Reference::Field { class, name, .. } => {
+            (class.get_name(), name)
+        }
+    }
+}
+
+impl fmt::Display for DiffEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = match self.change {
+            Change::Added => '+',
+            Change::Removed => '-',
+            Change::SignatureChanged { .. } => '~',
+        };
+        write!(f, "{} {}", sign, self.class)?;
+        if let Some(member) = &self.member {
+            write!(f, ".{}", member)?;
+        }
+        if let Change::SignatureChanged { from, to } = &self.change {
+            write!(f, ": {} -> {}", from, to)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MappingDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |kind: fn(&Change) -> bool| {
+            self.entries
+                .iter()
+                .filter(|entry| kind(&entry.change))
+                .count()
+        };
+        for entry in &self.entries {
+            writeln!(f, "{}", entry)?;
+        }
+        write!(
+            f,
+            "{} added, {} removed, {} signatures changed",
+            count(|change| *change == Change::Added),
+            count(|change| *change == Change::Removed),
+            count(|change| matches!(change, Change::SignatureChanged { .. }))
+        )
+    }
+}
+
+impl Mapping {
+    /// Lists the classes and members added, removed or with another descriptor in `new`
+    pub fn diff(&self, new: &Mapping) -> MappingDiff {
+        let old_classes: BTreeMap<&str, &MinecraftClass> = self.classes().collect();
+        let new_classes: BTreeMap<&str, &MinecraftClass> = new.classes().collect();
+        let mut diff = MappingDiff::default();
+
+        let names: BTreeSet<&str> = old_classes
+            .keys()
+            .chain(new_classes.keys())
+            .copied()
+            .collect();
+        for name in names {
+            let change = match (old_classes.get(name), new_classes.get(name)) {
+                (Some(old_class), Some(new_class)) => {
+                    self.diff_members(name, old_class, new, new_class, &mut diff);
+                    continue;
+                }
+                (Some(_), None) => Change::Removed,
+                (None, _) => Change::Added,
+            };
+            diff.entries.push(DiffEntry {
+                class: name.to_string(),
+                member: None,
+                change,
+            });
+        }
+
+        diff
+    }
+
+    fn diff_members(
+        &self,
+        class: &str,
+        old_class: &MinecraftClass,
+        new: &Mapping,
+        new_class: &MinecraftClass,
+        diff: &mut MappingDiff,
+    ) {
+        let mut push = |member: String, change: Change| {
+            diff.entries.push(DiffEntry {
+                class: class.to_string(),
+                member: Some(member),
+                change,
+            })
+        };
+
+        let method_names: BTreeSet<&String> = old_class
+            .methods
+            .keys()
+            .chain(new_class.methods.keys())
+            .collect();
+        for name in method_names {
+            let descriptors = |mapping: &Mapping, class: &MinecraftClass| -> BTreeSet<String> {
+                class
+                    .find_methods(name)
+                    .into_iter()
+                    .flatten()
+                    .map(|method| mapping.deobfuscate_descriptor(&method.signature))
+                    .collect()
+            };
+            let old_descriptors = descriptors(self, old_class);
+            let new_descriptors = descriptors(new, new_class);
+            let removed: Vec<&String> = old_descriptors.difference(&new_descriptors).collect();
+            let added: Vec<&String> = new_descriptors.difference(&old_descriptors).collect();
+
+            match (removed.as_slice(), added.as_slice()) {
+                ([from], [to]) => push(
+                    name.clone(),
+                    Change::SignatureChanged {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                    },
+                ),
+                _ => {
+                    for descriptor in removed {
+                        push(format!("{}{}", name, descriptor), Change::Removed);
+                    }
+                    for descriptor in added {
+                        push(format!("{}{}", name, descriptor), Change::Added);
+                    }
+                }
+            }
+        }
+
+        let field_names: BTreeSet<&String> = old_class
+            .fields
+            .keys()
+            .chain(new_class.fields.keys())
+            .collect();
+        for name in field_names {
+            match (old_class.find_field(name), new_class.find_field(name)) {
+                (Some(old_field), Some(new_field)) => {
+                    // Fields without a recorded type can't be compared
+                    if let (Some(from), Some(to)) = (&old_field.signature, &new_field.signature) {
+                        let from = self.deobfuscate_descriptor(from);
+                        let to = new.deobfuscate_descriptor(to);
+                        if from != to {
+                            push(name.clone(), Change::SignatureChanged { from, to });
+                        }
+                    }
+                }
+                (Some(_), None) => push(name.clone(), Change::Removed),
+                (None, _) => push(name.clone(), Change::Added),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        // Runtime names differ between the versions, only Mojang names are compared
+        let old = Mapping::from_json(
+            r#"{
+                "classes": {
+                    "net/minecraft/world/entity/Entity": {
+                        "name": "bxe",
+                        "methods": {
+                            "position": {"name": "dt", "signature": "()Lfby;"},
+                            "setPos": [
+                                {"name": "a", "signature": "(DDD)V"},
+                                {"name": "a", "signature": "(Lfby;)V"}
+                            ],
+                            "setInvulnerable": {"name": "m", "signature": "(Z)V"},
+                            "tick": {"name": "l", "signature": "()V"}
+                        },
+                        "fields": {"fallDistance": {"name": "ac", "signature": "F"}}
+                    },
+                    "net/minecraft/world/phys/Vec3": {"name": "fby", "methods": {}, "fields": {}},
+                    "net/minecraft/world/phys/AABB": {"name": "fbx", "methods": {}, "fields": {}}
+                }
+            }"#,
+        )
+        .unwrap();
+        let new = Mapping::from_json(
+            r#"{
+                "classes": {
+                    "net/minecraft/world/entity/Entity": {
+                        "name": "bxf",
+                        "methods": {
+                            "position": {"name": "du", "signature": "()Lfbz;"},
+                            "setPos": {"name": "b", "signature": "(Lfbz;)V"},
+                            "setInvulnerable": {"name": "n", "signature": "(ZZ)V"},
+                            "baseTick": {"name": "k", "signature": "()V"}
+                        },
+                        "fields": {"fallDistance": {"name": "ad", "signature": "D"}}
+                    },
+                    "net/minecraft/world/phys/Vec3": {"name": "fbz", "methods": {}, "fields": {}},
+                    "net/minecraft/world/phys/Vec2": {"name": "fbw", "methods": {}, "fields": {}}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.to_string(),
+            "+ net/minecraft/world/entity/Entity.baseTick()V\n\
+             ~ net/minecraft/world/entity/Entity.setInvulnerable: (Z)V -> (ZZ)V\n\
+             - net/minecraft/world/entity/Entity.setPos(DDD)V\n\
+             - net/minecraft/world/entity/Entity.tick()V\n\
+             ~ net/minecraft/world/entity/Entity.fallDistance: F -> D\n\
+             - net/minecraft/world/phys/AABB\n\
+             + net/minecraft/world/phys/Vec2\n\
+             2 added, 3 removed, 2 signatures changed"
+        );
+
+        let referenced = diff.referenced();
+        assert_eq!(
+            referenced
+                .entries
+                .iter()
+                .map(DiffEntry::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "~ net/minecraft/world/entity/Entity.setInvulnerable: (Z)V -> (ZZ)V",
+                "~ net/minecraft/world/entity/Entity.fallDistance: F -> D",
+            ]
+        );
+    }
+}
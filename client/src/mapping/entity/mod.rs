@@ -1,5 +1,10 @@
-use crate::mapping::{FieldType, GameContext, MinecraftClassType};
-use jni::objects::{GlobalRef, JValue};
+use crate::mapping::bindings::entity;
+use crate::mapping::chat::Component;
+use crate::mapping::error::MappingError;
+use crate::mapping::java::{convert_local, FromJava};
+use crate::mapping::phys::Vec3;
+use crate::mapping::{GameContext, Mapping};
+use jni::objects::{GlobalRef, JObject};
 
 pub mod player;
 
@@ -15,94 +20,49 @@ pub struct Entity {
 
 impl GameContext for Entity {}
 
+/// Lets entity collections be iterated as entities, e.g. `list.iter::<Entity>()`
+impl FromJava for Entity {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        GlobalRef::from_java(mapping, object).map(Entity::new)
+    }
+}
+
 impl Entity {
     pub fn new(jni_entity: GlobalRef) -> Entity {
         Entity { jni_entity }
     }
 
-    pub fn get_position(&self) -> (f64, f64, f64) {
+    pub fn get_position(&self) -> Result<(f64, f64, f64), MappingError> {
         let mapping = self.mapping();
 
-        let vec3 = mapping
-            .call_method(
-                MinecraftClassType::Entity,
-                self.jni_entity.as_obj(),
-                "position",
-                &[],
-            )
-            .l()
-            .unwrap();
-
-        let x = mapping
-            .get_field(MinecraftClassType::Vec3, &vec3, "x", FieldType::Double)
-            .d()
-            .unwrap();
-
-        let y = mapping
-            .get_field(MinecraftClassType::Vec3, &vec3, "y", FieldType::Double)
-            .d()
-            .unwrap();
-
-        let z = mapping
-            .get_field(MinecraftClassType::Vec3, &vec3, "z", FieldType::Double)
-            .d()
-            .unwrap();
+        let position = entity::position(mapping, self.jni_entity.as_obj())?;
+        let position = convert_local(mapping, position, Vec3::from_java)?;
 
-        (x, y, z)
+        Ok((position.x, position.y, position.z))
     }
 
-    pub fn set_invulnerable(&self, value: bool) {
-        let mapping = self.mapping();
-
-        mapping.call_method(
-            MinecraftClassType::Entity,
-            self.jni_entity.as_obj(),
-            "setInvulnerable",
-            &[JValue::from(value)],
-        );
+    pub fn set_invulnerable(&self, value: bool) -> Result<(), MappingError> {
+        entity::set_invulnerable(self.mapping(), self.jni_entity.as_obj(), value)
     }
 
-    pub fn get_fall_distance(&self) -> f64 {
-        let mapping = self.mapping();
-
-        mapping
-            .get_field(
-                MinecraftClassType::Entity,
-                self.jni_entity.as_obj(),
-                "fallDistance",
-                FieldType::Double,
-            )
-            .d()
-            .unwrap()
+    pub fn get_fall_distance(&self) -> Result<f64, MappingError> {
+        entity::get_fall_distance(self.mapping(), self.jni_entity.as_obj())
     }
 
-    pub fn reset_fall_distance(&self) {
-        let mapping = self.mapping();
+    pub fn reset_fall_distance(&self) -> Result<(), MappingError> {
+        entity::reset_fall_distance(self.mapping(), self.jni_entity.as_obj())
+    }
 
-        mapping
-            .call_method(
-                MinecraftClassType::Entity,
-                self.jni_entity.as_obj(),
-                "resetFallDistance",
-                &[],
-            )
-            .v()
-            .unwrap();
+    /// Name of the entity as plain text, translated in the language of the game
+    pub fn get_name(&self) -> Result<String, MappingError> {
+        self.get_name_component()?.get_string()
     }
 
-    pub fn get_name(&self) -> String {
+    /// Name of the entity with its styling, e.g. the colour of its team
+    pub fn get_name_component(&self) -> Result<Component, MappingError> {
         let mapping = self.mapping();
 
-        mapping.get_string(
-            mapping
-                .call_method(
-                    MinecraftClassType::Entity,
-                    self.jni_entity.as_obj(),
-                    "getName",
-                    &[],
-                )
-                .l()
-                .unwrap(),
-        )
+        let name = entity::get_name(mapping, self.jni_entity.as_obj())?;
+        convert_local(mapping, name, Component::from_java)
     }
 }
@@ -1,7 +1,9 @@
+use crate::mapping::bindings::{abilities, minecraft, player};
+use crate::mapping::chat::Component;
 use crate::mapping::entity::Entity;
-use crate::mapping::{FieldType, GameContext, Mapping, MinecraftClassType};
-use jni::objects::{GlobalRef, JValue};
-use jni::sys::jboolean;
+use crate::mapping::error::MappingError;
+use crate::mapping::{GameContext, Mapping};
+use jni::objects::GlobalRef;
 
 #[derive(Debug, Clone)]
 pub struct LocalPlayer {
@@ -19,73 +21,51 @@ impl GameContext for LocalPlayer {}
 impl GameContext for Abilities {}
 
 impl LocalPlayer {
-    pub fn new(minecraft: &GlobalRef, mapping: &Mapping) -> Self {
-        let player_obj = mapping
-            .get_field(
-                MinecraftClassType::Minecraft,
-                minecraft.as_obj(),
-                "player",
-                FieldType::Object(MinecraftClassType::LocalPlayer, mapping),
-            )
-            .l()
-            .unwrap();
+    pub fn new(minecraft: &GlobalRef, mapping: &Mapping) -> Result<Self, MappingError> {
+        let player_obj = minecraft::get_player(mapping, minecraft.as_obj())?;
 
-        let player_ref = mapping.new_global_ref(player_obj);
-        let abilities = Abilities::new(player_ref.clone(), mapping);
+        let player_ref = mapping.try_new_global_ref(player_obj)?;
+        let abilities = Abilities::new(player_ref.clone(), mapping)?;
         let entity = Entity::new(player_ref.clone());
 
-        Self {
+        Ok(Self {
             jni_ref: player_ref,
             abilities,
             entity,
-        }
+        })
+    }
+
+    /// Shows a message to the player only, in the chat or above the hotbar if `action_bar` is set
+    pub fn display_message(
+        &self,
+        message: &Component,
+        action_bar: bool,
+    ) -> Result<(), MappingError> {
+        player::display_client_message(
+            self.mapping(),
+            self.jni_ref.as_obj(),
+            message.jni_component.as_obj(),
+            action_bar,
+        )
     }
 }
 
 impl Abilities {
-    pub fn new(player: GlobalRef, mapping: &Mapping) -> Self {
-        let jni_ref = mapping
-            .call_method(MinecraftClassType::Player, &player, "getAbilities", &[])
-            .l()
-            .unwrap();
-        Self {
-            jni_ref: mapping.new_global_ref(jni_ref),
-        }
+    pub fn new(player: GlobalRef, mapping: &Mapping) -> Result<Self, MappingError> {
+        let jni_ref = player::get_abilities(mapping, &player)?;
+        Ok(Self {
+            jni_ref: mapping.try_new_global_ref(jni_ref)?,
+        })
     }
 
-    pub fn fly(&self, value: bool) {
+    pub fn fly(&self, value: bool) -> Result<(), MappingError> {
         let mapping = self.mapping();
 
-        let value: jboolean = if value { 1 } else { 0 };
-
-        mapping.set_field(
-            MinecraftClassType::Abilities,
-            self.jni_ref.as_obj(),
-            "flying",
-            FieldType::Boolean,
-            JValue::Bool(value),
-        );
-
-        mapping.set_field(
-            MinecraftClassType::Abilities,
-            self.jni_ref.as_obj(),
-            "mayfly",
-            FieldType::Boolean,
-            JValue::Bool(value),
-        );
+        abilities::set_flying(mapping, self.jni_ref.as_obj(), value)?;
+        abilities::set_mayfly(mapping, self.jni_ref.as_obj(), value)
     }
 
-    pub fn get_may_fly(&self) -> bool {
-        let mapping = self.mapping();
-
-        mapping
-            .get_field(
-                MinecraftClassType::Abilities,
-                self.jni_ref.as_obj(),
-                "mayfly",
-                FieldType::Boolean,
-            )
-            .z()
-            .unwrap()
+    pub fn get_may_fly(&self) -> Result<bool, MappingError> {
+        abilities::get_mayfly(self.mapping(), self.jni_ref.as_obj())
     }
 }
@@ -0,0 +1,246 @@
+use jni::objects::{JObject, JObjectArray, JString, JThrowable};
+use jni::JNIEnv;
+use std::fmt;
+
+/// Error raised while resolving or accessing a mapped class, method or field
+///
+/// Class and member names are the Mojang names used as keys of the mapping.
+#[derive(Debug)]
+pub enum MappingError {
+    MissingClass(String),
+    MissingMethod {
+        class: String,
+        method: String,
+    },
+    MissingField {
+        class: String,
+        field: String,
+    },
+    /// The member exists, but not with the type or arguments it was accessed with
+    SignatureMismatch {
+        class: String,
+        member: String,
+        expected: String,
+        found: String,
+    },
+    /// Several overloads match the arguments, the call must select one with its parameter types
+    AmbiguousMethod {
+        class: String,
+        method: String,
+        candidates: Vec<String>,
+    },
+    /// A Java exception was thrown, it has already been cleared
+    JavaException {
+        class: String,
+        message: Option<String>,
+        stack_trace: Vec<StackFrame>,
+    },
+    NullReceiver {
+        class: String,
+        member: String,
+    },
+    /// A Java value can't be converted to its Rust type
+    InvalidValue {
+        class: String,
+        message: String,
+    },
+    Jni(jni::errors::Error),
+}
+
+/// Element of a Java stack trace
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrame {
+    /// Binary name of the class, e.g. `net.minecraft.client.Minecraft$1`
+    pub class: String,
+    pub method: String,
+    pub file: Option<String>,
+    /// Negative if unknown, `-2` for native methods
+    pub line: i32,
+}
+
+impl fmt::Display for StackFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}.{}(", self.class, self.method)?;
+        match (&self.file, self.line) {
+            (_, -2) => write!(f, "Native Method)"),
+            (Some(file), line) if line >= 0 => write!(f, "{}:{})", file, line),
+            (Some(file), _) => write!(f, "{})", file),
+            (None, _) => write!(f, "Unknown Source)"),
+        }
+    }
+}
+
+impl MappingError {
+    /// Converts a JNI error, describing and clearing the pending exception if one was thrown
+    pub fn from_jni(env: &mut JNIEnv, error: jni::errors::Error) -> MappingError {
+        match error {
+            jni::errors::Error::JavaException => Self::take_exception(env)
+                .unwrap_or(MappingError::Jni(jni::errors::Error::JavaException)),
+            error => MappingError::Jni(error),
+        }
+    }
+
+    /// Clears the pending Java exception and returns it, `None` if there is none
+    pub fn take_exception(env: &mut JNIEnv) -> Option<MappingError> {
+        if !env.exception_check().unwrap_or(false) {
+            return None;
+        }
+
+        let throwable = env.exception_occurred().ok()?;
+        env.exception_clear().ok()?;
+
+        let error = describe_exception(env, &throwable).unwrap_or_else(|_| {
+            // A getter that threw while describing leaves its own exception pending
+            let _ = env.exception_clear();
+            MappingError::JavaException {
+                class: "java.lang.Throwable".to_string(),
+                message: None,
+                stack_trace: Vec::new(),
+            }
+        });
+        let _ = env.delete_local_ref(throwable);
+        Some(error)
+    }
+}
+
+/// Reads the class name, message and stack trace of a throwable, which must not be pending anymore
+fn describe_exception(
+    env: &mut JNIEnv,
+    throwable: &JThrowable,
+) -> jni::errors::Result<MappingError> {
+    let class = env
+        .call_method(throwable, "getClass", "()Ljava/lang/Class;", &[])?
+        .l()?;
+    let class_name = env
+        .call_method(&class, "getName", "()Ljava/lang/String;", &[])?
+        .l()?;
+    env.delete_local_ref(class)?;
+    let message = env
+        .call_method(throwable, "getMessage", "()Ljava/lang/String;", &[])?
+        .l()?;
+
+    Ok(MappingError::JavaException {
+        class: java_string(env, class_name)?.unwrap_or_default(),
+        message: java_string(env, message)?,
+        stack_trace: stack_trace(env, throwable)?,
+    })
+}
+
+fn stack_trace(env: &mut JNIEnv, throwable: &JThrowable) -> jni::errors::Result<Vec<StackFrame>> {
+    let elements = JObjectArray::from(
+        env.call_method(
+            throwable,
+            "getStackTrace",
+            "()[Ljava/lang/StackTraceElement;",
+            &[],
+        )?
+        .l()?,
+    );
+
+    let mut frames = Vec::new();
+    for index in 0..env.get_array_length(&elements)? {
+        let frame = env.with_local_frame(8, |env| -> jni::errors::Result<StackFrame> {
+            let element = env.get_object_array_element(&elements, index)?;
+            let class = env
+                .call_method(&element, "getClassName", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let method = env
+                .call_method(&element, "getMethodName", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let file = env
+                .call_method(&element, "getFileName", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let line = env
+                .call_method(&element, "getLineNumber", "()I", &[])?
+                .i()?;
+
+            Ok(StackFrame {
+                class: java_string(env, class)?.unwrap_or_default(),
+                method: java_string(env, method)?.unwrap_or_default(),
+                file: java_string(env, file)?,
+                line,
+            })
+        })?;
+        frames.push(frame);
+    }
+    env.delete_local_ref(elements)?;
+
+    Ok(frames)
+}
+
+fn java_string(env: &mut JNIEnv, object: JObject) -> jni::errors::Result<Option<String>> {
+    if object.is_null() {
+        return Ok(None);
+    }
+
+    let string = JString::from(object);
+    let value = env.get_string(&string)?.into();
+    env.delete_local_ref(string)?;
+    Ok(Some(value))
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingError::MissingClass(class) => write!(f, "class {} is not mapped", class),
+            MappingError::MissingMethod { class, method } => {
+                write!(f, "method {}.{} is not mapped", class, method)
+            }
+            MappingError::MissingField { class, field } => {
+                write!(f, "field {}.{} is not mapped", class, field)
+            }
+            MappingError::SignatureMismatch {
+                class,
+                member,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}.{} expects {}, but it was accessed with {}",
+                class, member, expected, found
+            ),
+            MappingError::AmbiguousMethod {
+                class,
+                method,
+                candidates,
+            } => write!(
+                f,
+                "{}.{} is ambiguous, it matches {}",
+                class,
+                method,
+                candidates.join(", ")
+            ),
+            MappingError::JavaException {
+                class,
+                message,
+                stack_trace,
+            } => {
+                match message {
+                    Some(message) => write!(f, "{}: {}", class, message)?,
+                    None => write!(f, "{}", class)?,
+                }
+                for frame in stack_trace {
+                    write!(f, "\n\t{}", frame)?;
+                }
+                Ok(())
+            }
+            MappingError::NullReceiver { class, member } => {
+                write!(f, "{}.{} accessed on a null instance", class, member)
+            }
+            MappingError::InvalidValue { class, message } => {
+                write!(f, "invalid {} value: {}", class, message)
+            }
+            MappingError::Jni(e) => write!(f, "JNI error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MappingError {}
+
+/// Only meant for errors that can't come from a thrown exception, like [`jni::objects::JValueOwned::l`],
+/// use [`MappingError::from_jni`] otherwise
+impl From<jni::errors::Error> for MappingError {
+    fn from(error: jni::errors::Error) -> Self {
+        MappingError::Jni(error)
+    }
+}
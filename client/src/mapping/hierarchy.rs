@@ -0,0 +1,364 @@
+use crate::mapping::error::MappingError;
+use crate::mapping::{Mapping, MinecraftClass};
+use jni::objects::{JObject, JObjectArray, JString};
+use jni::JNIEnv;
+use log::warn;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Mojang names of the superclass and interfaces of a class, read from the JVM
+#[derive(Debug, Default)]
+struct Parents {
+    superclass: Option<String>,
+    interfaces: Vec<String>,
+}
+
+/// Reads the parents of a class from the JVM
+type ParentsReader = fn(&Mapping, &MinecraftClass) -> Result<Parents, MappingError>;
+
+/// Parents of the classes whose hierarchy isn't in the mapping file, read the first time they are looked up
+#[derive(Debug, Default)]
+pub struct DiscoveredHierarchy {
+    /// Set once the mapping is used by the running game, there is no JVM to read from before
+    ///
+    /// Kept as a function pointer so tools that never start the game don't link against the JVM.
+    reader: Option<ParentsReader>,
+    parents: RwLock<HashMap<String, Arc<Parents>>>,
+}
+
+/// Parents of a class, from the mapping file or discovered
+enum ParentsRef<'a> {
+    Mapped(&'a MinecraftClass),
+    Discovered(Arc<Parents>),
+}
+
+impl ParentsRef<'_> {
+    fn superclass(&self) -> Option<&str> {
+        match self {
+            ParentsRef::Mapped(class) => class.superclass.as_deref(),
+            ParentsRef::Discovered(parents) => parents.superclass.as_deref(),
+        }
+    }
+
+    fn interfaces(&self) -> &[String] {
+        match self {
+            ParentsRef::Mapped(class) => &class.interfaces,
+            ParentsRef::Discovered(parents) => &parents.interfaces,
+        }
+    }
+}
+
+impl Mapping {
+    /// Returns the class followed by its mapped ancestors in lookup order
+    ///
+    /// The superclass chain comes first, then the interfaces of every class in the chain and their
+    /// superinterfaces, like the JVM method resolution. Unmapped ancestors end the chain.
+    pub fn hierarchy(&self, name: &str) -> Vec<&MinecraftClass> {
+        let mut classes: Vec<(&str, &MinecraftClass)> = Vec::new();
+
+        let mut current = self.class_entry(name);
+        while let Some((name, class)) = current {
+            if classes.iter().any(|(visited, _)| *visited == name) {
+                break;
+            }
+            classes.push((name, class));
+            current = self
+                .parents(name, class)
+                .superclass()
+                .and_then(|superclass| self.class_entry(superclass));
+        }
+
+        let mut index = 0;
+        while index < classes.len() {
+            let (name, class) = classes[index];
+            for interface in self.parents(name, class).interfaces() {
+                if let Some((name, interface)) = self.class_entry(interface) {
+                    if !classes.iter().any(|(visited, _)| *visited == name) {
+                        classes.push((name, interface));
+                    }
+                }
+            }
+            index += 1;
+        }
+
+        classes.into_iter().map(|(_, class)| class).collect()
+    }
+
+    /// Reads the superclass and interfaces of the classes from the running JVM the first time they are
+    /// looked up, for every class whose hierarchy isn't in the mapping file
+    pub fn discover_hierarchy(&mut self) {
+        self.discovered.reader = Some(Mapping::discover_parents);
+    }
+
+    /// Sets the Mojang names of the superclass and interfaces of a class, returns `false` if it isn't mapped
+    pub fn set_hierarchy(
+        &mut self,
+        name: &str,
+        superclass: Option<String>,
+        interfaces: Vec<String>,
+    ) -> bool {
+        match self.class_mut(name) {
+            Some(class) => {
+                class.superclass = superclass;
+                class.interfaces = interfaces;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn parents<'a>(&self, name: &str, class: &'a MinecraftClass) -> ParentsRef<'a> {
+        let Some(reader) = self.discovered.reader else {
+            return ParentsRef::Mapped(class);
+        };
+        if class.superclass.is_some() || !class.interfaces.is_empty() {
+            return ParentsRef::Mapped(class);
+        }
+        if let Some(parents) = self.discovered.parents.read().unwrap().get(name) {
+            return ParentsRef::Discovered(parents.clone());
+        }
+
+        let parents = Arc::new(reader(self, class).unwrap_or_else(|e| {
+            warn!("Unable to read the hierarchy of {}: {}", name, e);
+            Parents::default()
+        }));
+        self.discovered
+            .parents
+            .write()
+            .unwrap()
+            .insert(name.to_string(), parents.clone());
+        ParentsRef::Discovered(parents)
+    }
+
+    /// Reads the mapped superclass and interfaces of a class
+    ///
+    /// Unmapped superclasses are skipped, but their interfaces are kept since their members are inherited.
+    fn discover_parents(&self, class: &MinecraftClass) -> Result<Parents, MappingError> {
+        let mut env = self.try_get_env()?;
+        let reverse = self.reverse();
+
+        env.with_local_frame(32, |env| {
+            let mut current = self
+                .cache
+                .load_class(env, &class.name)
+                .map_err(|e| MappingError::from_jni(env, e))?;
+            let mut parents = Parents::default();
+
+            loop {
+                let interfaces = JObjectArray::from(call_object(
+                    env,
+                    &current,
+                    "getInterfaces",
+                    "()[Ljava/lang/Class;",
+                )?);
+                for index in 0..env.get_array_length(&interfaces)? {
+                    let interface = env.get_object_array_element(&interfaces, index)?;
+                    if let Some(name) = reverse.class_name(&class_name(env, &interface)?) {
+                        if !parents.interfaces.iter().any(|known| known == name) {
+                            parents.interfaces.push(name.to_string());
+                        }
+                    }
+                    env.delete_local_ref(interface)?;
+                }
+
+                let superclass =
+                    call_object(env, &current, "getSuperclass", "()Ljava/lang/Class;")?;
+                if superclass.is_null() {
+                    return Ok(parents);
+                }
+                if let Some(name) = reverse.class_name(&class_name(env, &superclass)?) {
+                    parents.superclass = Some(name.to_string());
+                    return Ok(parents);
+                }
+                current = superclass;
+            }
+        })
+    }
+}
+
+fn call_object<'local>(
+    env: &mut JNIEnv<'local>,
+    object: &JObject,
+    name: &str,
+    signature: &str,
+) -> Result<JObject<'local>, MappingError> {
+    env.call_method(object, name, signature, &[])
+        .and_then(|value| value.l())
+        .map_err(|e| MappingError::from_jni(env, e))
+}
+
+/// Returns the internal name of a `java.lang.Class`, e.g. `net/minecraft/client/Minecraft`
+fn class_name(env: &mut JNIEnv, class: &JObject) -> Result<String, MappingError> {
+    let name = JString::from(call_object(env, class, "getName", "()Ljava/lang/String;")?);
+    let value: String = env.get_string(&name)?.into();
+    env.delete_local_ref(name)?;
+    Ok(value.replace('.', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::MinecraftClassType;
+    use jni::objects::JValue;
+
+    fn mapping() -> Mapping {
+        serde_json::from_str(
+            r#"{
+                "classes": {
+                    "net/minecraft/client/player/LocalPlayer": {
+                        "name": "gkv",
+                        "methods": {},
+                        "fields": {},
+                        "superclass": "net/minecraft/client/player/AbstractClientPlayer"
+                    },
+                    "net/minecraft/client/player/AbstractClientPlayer": {
+                        "name": "gkp",
+                        "methods": {},
+                        "fields": {},
+                        "superclass": "net/minecraft/world/entity/player/Player"
+                    },
+                    "net/minecraft/world/entity/player/Player": {
+                        "name": "cpi",
+                        "methods": {"getAbilities": {"name": "gq", "signature": "()Lcph;"}},
+                        "fields": {},
+                        "superclass": "net/minecraft/world/entity/Entity"
+                    },
+                    "net/minecraft/world/entity/Entity": {
+                        "name": "bxe",
+                        "methods": {"position": {"name": "dt", "signature": "()Lfby;"}},
+                        "fields": {"fallDistance": {"name": "ac", "signature": "D"}},
+                        "interfaces": ["net/minecraft/world/Nameable"]
+                    },
+                    "net/minecraft/world/Nameable": {
+                        "name": "ya",
+                        "methods": {"getName": {"name": "ai", "signature": "()Lyh;"}},
+                        "fields": {}
+                    },
+                    "net/minecraft/world/phys/Vec3": {
+                        "name": "fby",
+                        "methods": {},
+                        "fields": {},
+                        "superclass": "net/minecraft/world/phys/Vec3"
+                    }
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_hierarchy_order() {
+        let mapping = mapping();
+
+        let names: Vec<&str> = mapping
+            .hierarchy("net/minecraft/client/player/LocalPlayer")
+            .iter()
+            .map(|class| class.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["gkv", "gkp", "cpi", "bxe", "ya"]);
+
+        // A class listed as its own superclass doesn't loop
+        assert_eq!(mapping.hierarchy("net/minecraft/world/phys/Vec3").len(), 1);
+        assert!(mapping.hierarchy("net/minecraft/Unknown").is_empty());
+    }
+
+    #[test]
+    fn test_inherited_members() {
+        let mapping = mapping();
+
+        let get_abilities = mapping
+            .resolve_method(MinecraftClassType::LocalPlayer, "getAbilities", &[])
+            .unwrap();
+        assert_eq!(get_abilities.name, "gq");
+
+        let get_name = mapping
+            .resolve_method(MinecraftClassType::LocalPlayer, "getName", &[])
+            .unwrap();
+        assert_eq!(get_name.name, "ai");
+
+        let fall_distance = mapping
+            .resolve_field(MinecraftClassType::LocalPlayer, "fallDistance")
+            .unwrap();
+        assert_eq!(fall_distance.name, "ac");
+
+        // Lookups never go down the hierarchy
+        assert!(matches!(
+            mapping.resolve_method(MinecraftClassType::Entity, "getAbilities", &[]),
+            Err(MappingError::MissingMethod { .. })
+        ));
+        assert!(matches!(
+            mapping.resolve_method(
+                MinecraftClassType::LocalPlayer,
+                "setPos",
+                &[JValue::Double(0.0)]
+            ),
+            Err(MappingError::MissingMethod { .. })
+        ));
+    }
+
+    #[test]
+    fn test_set_hierarchy() {
+        let mut mapping = mapping();
+
+        assert!(mapping.set_hierarchy("net/minecraft/world/entity/Entity", None, Vec::new()));
+        assert!(mapping
+            .resolve_method(MinecraftClassType::Player, "getName", &[])
+            .is_err());
+        assert!(!mapping.set_hierarchy("net/minecraft/Unknown", None, Vec::new()));
+    }
+
+    /// Stands in for the JVM, `Vec3` has no hierarchy in the file
+    fn read_parents(_: &Mapping, class: &MinecraftClass) -> Result<Parents, MappingError> {
+        match class.name.as_str() {
+            "fby" => Ok(Parents {
+                superclass: None,
+                interfaces: vec!["net/minecraft/core/Position".to_string()],
+            }),
+            "jb" => Ok(Parents {
+                superclass: None,
+                interfaces: vec!["net/minecraft/world/Nameable".to_string()],
+            }),
+            _ => Ok(Parents::default()),
+        }
+    }
+
+    #[test]
+    fn test_discovered_hierarchy() {
+        let mut mapping: Mapping = serde_json::from_str(
+            r#"{
+                "classes": {
+                    "net/minecraft/world/phys/Vec3": {"name": "fby", "methods": {}, "fields": {}},
+                    "net/minecraft/core/Position": {"name": "jb", "methods": {}, "fields": {}},
+                    "net/minecraft/world/Nameable": {
+                        "name": "ya",
+                        "methods": {"getName": {"name": "ai", "signature": "()Lyh;"}},
+                        "fields": {}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        // Nothing is read before the game uses the mapping
+        assert_eq!(mapping.hierarchy("net/minecraft/world/phys/Vec3").len(), 1);
+
+        mapping.discovered.reader = Some(read_parents);
+        let names: Vec<&str> = mapping
+            .hierarchy("net/minecraft/world/phys/Vec3")
+            .iter()
+            .map(|class| class.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["fby", "jb", "ya"]);
+        assert_eq!(
+            mapping
+                .resolve_method(
+                    MinecraftClassType::new("net/minecraft/world/phys/Vec3"),
+                    "getName",
+                    &[]
+                )
+                .unwrap()
+                .name,
+            "ai"
+        );
+    }
+}
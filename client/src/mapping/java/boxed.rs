@@ -0,0 +1,43 @@
+use crate::mapping::error::MappingError;
+use crate::mapping::java::{call_method, call_static_method, check_not_null, FromJava, IntoJava};
+use crate::mapping::Mapping;
+use jni::objects::{JObject, JValue};
+
+/// Boxed primitive, unboxed with its getter and boxed with `valueOf`
+///
+/// Numbers are read through `java.lang.Number`, so e.g. an `Integer` can be read as `f64`.
+macro_rules! boxed_primitive {
+    ($type:ty, $class:literal, $getter:literal, $descriptor:literal, $value:ident) => {
+        impl FromJava for $type {
+            fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+                check_not_null(object, $class)?;
+                Ok(
+                    call_method(mapping, object, $getter, concat!("()", $descriptor), &[])?
+                        .$value()?,
+                )
+            }
+        }
+
+        impl IntoJava for $type {
+            fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+                Ok(call_static_method(
+                    mapping,
+                    $class,
+                    "valueOf",
+                    concat!("(", $descriptor, ")L", $class, ";"),
+                    &[JValue::from(*self)],
+                )?
+                .l()?)
+            }
+        }
+    };
+}
+
+boxed_primitive!(bool, "java/lang/Boolean", "booleanValue", "Z", z);
+boxed_primitive!(i8, "java/lang/Byte", "byteValue", "B", b);
+boxed_primitive!(u16, "java/lang/Character", "charValue", "C", c);
+boxed_primitive!(i16, "java/lang/Short", "shortValue", "S", s);
+boxed_primitive!(i32, "java/lang/Integer", "intValue", "I", i);
+boxed_primitive!(i64, "java/lang/Long", "longValue", "J", j);
+boxed_primitive!(f32, "java/lang/Float", "floatValue", "F", f);
+boxed_primitive!(f64, "java/lang/Double", "doubleValue", "D", d);
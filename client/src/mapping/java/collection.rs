@@ -0,0 +1,287 @@
+use crate::mapping::error::MappingError;
+use crate::mapping::java::{call_method, convert_local, FromJava};
+use crate::mapping::{GameContext, Mapping};
+use jni::objects::{GlobalRef, JObject, JValue};
+
+type Converter<T> = fn(&Mapping, &JObject) -> Result<T, MappingError>;
+
+/// `java.util.Iterator` whose elements are converted with [`FromJava`]
+///
+/// Every element is a local reference freed once converted, so iterating a large collection doesn't fill
+/// the local reference table. The iteration stops after the first error.
+pub struct JavaIterator<T> {
+    pub jni_iterator: GlobalRef,
+    convert: Converter<T>,
+    finished: bool,
+}
+
+impl<T> GameContext for JavaIterator<T> {}
+
+impl<T: FromJava> JavaIterator<T> {
+    pub fn new(jni_iterator: GlobalRef) -> Self {
+        Self::with_converter(jni_iterator, T::from_java)
+    }
+
+    /// Iterates a `java.lang.Iterable`, e.g. the entities returned by `ClientLevel.entitiesForRendering()`
+    pub fn from_iterable(mapping: &Mapping, iterable: &JObject) -> Result<Self, MappingError> {
+        Self::from_call(mapping, iterable, "iterator", T::from_java)
+    }
+}
+
+impl<T> JavaIterator<T> {
+    fn with_converter(jni_iterator: GlobalRef, convert: Converter<T>) -> Self {
+        Self {
+            jni_iterator,
+            convert,
+            finished: false,
+        }
+    }
+
+    /// Calls a method of `object` returning an iterator, like `iterator()` or `keySet().iterator()`
+    fn from_call(
+        mapping: &Mapping,
+        object: &JObject,
+        method: &str,
+        convert: Converter<T>,
+    ) -> Result<Self, MappingError> {
+        let iterator = call_object(mapping, object, method, "()Ljava/util/Iterator;")?;
+        let jni_iterator = convert_local(mapping, iterator, GlobalRef::from_java)?;
+        Ok(Self::with_converter(jni_iterator, convert))
+    }
+
+    fn next_element(&self) -> Result<Option<T>, MappingError> {
+        let mapping = self.mapping();
+        let iterator = self.jni_iterator.as_obj();
+
+        if !call_method(mapping, iterator, "hasNext", "()Z", &[])?.z()? {
+            return Ok(None);
+        }
+        let element = call_object(mapping, iterator, "next", "()Ljava/lang/Object;")?;
+        convert_local(mapping, element, self.convert).map(Some)
+    }
+}
+
+impl<T> Iterator for JavaIterator<T> {
+    type Item = Result<T, MappingError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let element = self.next_element().transpose();
+        self.finished = !matches!(element, Some(Ok(_)));
+        element
+    }
+}
+
+/// `java.util.List`, elements are read with [`FromJava`]
+#[derive(Debug, Clone)]
+pub struct JavaList {
+    pub jni_list: GlobalRef,
+}
+
+impl GameContext for JavaList {}
+
+impl FromJava for JavaList {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        GlobalRef::from_java(mapping, object).map(Self::new)
+    }
+}
+
+impl JavaList {
+    pub fn new(jni_list: GlobalRef) -> Self {
+        Self { jni_list }
+    }
+
+    pub fn len(&self) -> Result<usize, MappingError> {
+        size(self.mapping(), &self.jni_list)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, MappingError> {
+        is_empty(self.mapping(), &self.jni_list)
+    }
+
+    /// Returns the element at `index`, an index out of bounds fails with the `IndexOutOfBoundsException`
+    /// and one above `i32::MAX` with [`MappingError::InvalidValue`]
+    pub fn get<T: FromJava>(&self, index: usize) -> Result<T, MappingError> {
+        let mapping = self.mapping();
+        let index = i32::try_from(index).map_err(|_| MappingError::InvalidValue {
+            class: "java/util/List".to_string(),
+            message: format!("index {} doesn't fit in an int", index),
+        })?;
+        let element = call_method(
+            mapping,
+            &self.jni_list,
+            "get",
+            "(I)Ljava/lang/Object;",
+            &[JValue::Int(index)],
+        )?
+        .l()?;
+        convert_local(mapping, element, T::from_java)
+    }
+
+    /// Returns true if the list has an element equal to `value`, compared with `equals`
+    pub fn contains(&self, value: &JObject) -> Result<bool, MappingError> {
+        contains(self.mapping(), &self.jni_list, "contains", value)
+    }
+
+    pub fn iter<T: FromJava>(&self) -> Result<JavaIterator<T>, MappingError> {
+        JavaIterator::from_iterable(self.mapping(), &self.jni_list)
+    }
+}
+
+/// `java.util.Set`, elements are read with [`FromJava`]
+#[derive(Debug, Clone)]
+pub struct JavaSet {
+    pub jni_set: GlobalRef,
+}
+
+impl GameContext for JavaSet {}
+
+impl FromJava for JavaSet {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        GlobalRef::from_java(mapping, object).map(Self::new)
+    }
+}
+
+impl JavaSet {
+    pub fn new(jni_set: GlobalRef) -> Self {
+        Self { jni_set }
+    }
+
+    pub fn len(&self) -> Result<usize, MappingError> {
+        size(self.mapping(), &self.jni_set)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, MappingError> {
+        is_empty(self.mapping(), &self.jni_set)
+    }
+
+    pub fn contains(&self, value: &JObject) -> Result<bool, MappingError> {
+        contains(self.mapping(), &self.jni_set, "contains", value)
+    }
+
+    pub fn iter<T: FromJava>(&self) -> Result<JavaIterator<T>, MappingError> {
+        JavaIterator::from_iterable(self.mapping(), &self.jni_set)
+    }
+}
+
+/// `java.util.Map`, keys and values are read with [`FromJava`]
+#[derive(Debug, Clone)]
+pub struct JavaMap {
+    pub jni_map: GlobalRef,
+}
+
+impl GameContext for JavaMap {}
+
+impl FromJava for JavaMap {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        GlobalRef::from_java(mapping, object).map(Self::new)
+    }
+}
+
+impl JavaMap {
+    pub fn new(jni_map: GlobalRef) -> Self {
+        Self { jni_map }
+    }
+
+    pub fn len(&self) -> Result<usize, MappingError> {
+        size(self.mapping(), &self.jni_map)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, MappingError> {
+        is_empty(self.mapping(), &self.jni_map)
+    }
+
+    /// Returns the value of `key`, `None` if the map has no such key or maps it to `null`
+    pub fn get<V: FromJava>(&self, key: &JObject) -> Result<Option<V>, MappingError> {
+        let mapping = self.mapping();
+        let value = call_method(
+            mapping,
+            &self.jni_map,
+            "get",
+            "(Ljava/lang/Object;)Ljava/lang/Object;",
+            &[JValue::Object(key)],
+        )?
+        .l()?;
+        convert_local(mapping, value, Option::<V>::from_java)
+    }
+
+    pub fn contains_key(&self, key: &JObject) -> Result<bool, MappingError> {
+        contains(self.mapping(), &self.jni_map, "containsKey", key)
+    }
+
+    pub fn keys<K: FromJava>(&self) -> Result<JavaIterator<K>, MappingError> {
+        self.view_iterator("keySet", "()Ljava/util/Set;", K::from_java)
+    }
+
+    pub fn values<V: FromJava>(&self) -> Result<JavaIterator<V>, MappingError> {
+        self.view_iterator("values", "()Ljava/util/Collection;", V::from_java)
+    }
+
+    /// Iterates the entries as `(key, value)` pairs
+    pub fn iter<K: FromJava, V: FromJava>(&self) -> Result<JavaIterator<(K, V)>, MappingError> {
+        self.view_iterator("entrySet", "()Ljava/util/Set;", entry::<K, V>)
+    }
+
+    /// Iterates a collection view of the map, like `keySet()`, freeing the view once the iterator is created
+    fn view_iterator<T>(
+        &self,
+        view: &str,
+        signature: &str,
+        convert: Converter<T>,
+    ) -> Result<JavaIterator<T>, MappingError> {
+        let mapping = self.mapping();
+        let view = call_object(mapping, &self.jni_map, view, signature)?;
+        convert_local(mapping, view, |mapping, view| {
+            JavaIterator::from_call(mapping, view, "iterator", convert)
+        })
+    }
+}
+
+/// Reads a `java.util.Map.Entry` as a `(key, value)` pair
+fn entry<K: FromJava, V: FromJava>(
+    mapping: &Mapping,
+    entry: &JObject,
+) -> Result<(K, V), MappingError> {
+    let key = call_object(mapping, entry, "getKey", "()Ljava/lang/Object;")?;
+    let key = convert_local(mapping, key, K::from_java)?;
+    let value = call_object(mapping, entry, "getValue", "()Ljava/lang/Object;")?;
+    let value = convert_local(mapping, value, V::from_java)?;
+    Ok((key, value))
+}
+
+fn call_object<'a>(
+    mapping: &'a Mapping,
+    object: &JObject,
+    name: &str,
+    signature: &str,
+) -> Result<JObject<'a>, MappingError> {
+    Ok(call_method(mapping, object, name, signature, &[])?.l()?)
+}
+
+fn size(mapping: &Mapping, collection: &JObject) -> Result<usize, MappingError> {
+    let size = call_method(mapping, collection, "size", "()I", &[])?.i()?;
+    Ok(size as usize)
+}
+
+fn is_empty(mapping: &Mapping, collection: &JObject) -> Result<bool, MappingError> {
+    Ok(call_method(mapping, collection, "isEmpty", "()Z", &[])?.z()?)
+}
+
+fn contains(
+    mapping: &Mapping,
+    collection: &JObject,
+    method: &str,
+    value: &JObject,
+) -> Result<bool, MappingError> {
+    Ok(call_method(
+        mapping,
+        collection,
+        method,
+        "(Ljava/lang/Object;)Z",
+        &[JValue::Object(value)],
+    )?
+    .z()?)
+}
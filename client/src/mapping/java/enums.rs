@@ -0,0 +1,158 @@
+use crate::mapping::check::Reference;
+use crate::mapping::error::MappingError;
+use crate::mapping::java::{call_method, check_not_null, convert_local, FromJava};
+use crate::mapping::{FieldType, Mapping, MinecraftClassType};
+use jni::objects::{GlobalRef, JObject};
+
+/// Declares a Rust enum for a mapped Java enum, with [`FromJava`](crate::mapping::java::FromJava) and
+/// [`IntoJava`](crate::mapping::java::IntoJava) implementations
+///
+/// Each variant names the Mojang name of its constant field. Objects are matched by identity with the
+/// constants, so the order of the constants in the game doesn't matter.
+macro_rules! java_enum {
+    ($(#[$meta:meta])* $name:ident: $class:literal { $($variant:ident = $field:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl $name {
+            pub const CLASS: $crate::mapping::MinecraftClassType =
+                $crate::mapping::MinecraftClassType::new($class);
+            pub const CONSTANTS: &'static [(Self, &'static str)] = &[$(($name::$variant, $field)),+];
+            /// Constant fields, checked by [`Mapping::self_check`](crate::mapping::Mapping::self_check)
+            pub const REFERENCES: &'static [$crate::mapping::check::Reference] = &[$(
+                $crate::mapping::check::Reference::Field {
+                    class: Self::CLASS,
+                    name: $field,
+                    field_type: |mapping| {
+                        $crate::mapping::FieldType::Object(
+                            $crate::mapping::MinecraftClassType::new($class),
+                            mapping,
+                        )
+                    },
+                }
+            ),+];
+
+            /// Mojang name of the constant field
+            pub fn field_name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $field),+
+                }
+            }
+        }
+
+        impl $crate::mapping::java::FromJava for $name {
+            fn from_java(
+                mapping: &$crate::mapping::Mapping,
+                object: &jni::objects::JObject,
+            ) -> Result<Self, $crate::mapping::error::MappingError> {
+                $crate::mapping::java::enums::enum_constant(
+                    mapping,
+                    object,
+                    Self::CLASS,
+                    Self::CONSTANTS,
+                )
+            }
+        }
+
+        impl $crate::mapping::java::IntoJava for $name {
+            fn to_java<'a>(
+                &self,
+                mapping: &'a $crate::mapping::Mapping,
+            ) -> Result<jni::objects::JObject<'a>, $crate::mapping::error::MappingError> {
+                $crate::mapping::java::enums::constant_object(mapping, Self::CLASS, self.field_name())
+            }
+        }
+    };
+}
+
+java_enum! {
+    /// `net.minecraft.world.level.GameType`, the game mode of a player
+    GameType: "net/minecraft/world/level/GameType" {
+        Survival = "SURVIVAL",
+        Creative = "CREATIVE",
+        Adventure = "ADVENTURE",
+        Spectator = "SPECTATOR",
+    }
+}
+
+java_enum! {
+    /// `net.minecraft.core.Direction`
+    Direction: "net/minecraft/core/Direction" {
+        Down = "DOWN",
+        Up = "UP",
+        North = "NORTH",
+        South = "SOUTH",
+        West = "WEST",
+        East = "EAST",
+    }
+}
+
+java_enum! {
+    /// `net.minecraft.world.InteractionHand`
+    InteractionHand: "net/minecraft/world/InteractionHand" {
+        MainHand = "MAIN_HAND",
+        OffHand = "OFF_HAND",
+    }
+}
+
+/// Constants of the enums declared above, checked by [`Mapping::self_check`]
+pub const ENUM_REFERENCES: &[&[Reference]] = &[
+    GameType::REFERENCES,
+    Direction::REFERENCES,
+    InteractionHand::REFERENCES,
+];
+
+/// Reads the constant of an enum field, see [`java_enum`]
+pub fn constant_object<'a>(
+    mapping: &'a Mapping,
+    class_type: MinecraftClassType,
+    field_name: &str,
+) -> Result<JObject<'a>, MappingError> {
+    Ok(mapping
+        .try_get_static_field(
+            class_type,
+            field_name,
+            FieldType::Object(class_type, mapping),
+        )?
+        .l()?)
+}
+
+/// Returns the variant whose constant is `object`
+///
+/// The constants are read once per enum and kept as global references in the resolution cache. A
+/// constant missing from `constants`, e.g. added by a newer game version, fails with its Java name.
+pub fn enum_constant<E: Copy>(
+    mapping: &Mapping,
+    object: &JObject,
+    class_type: MinecraftClassType,
+    constants: &[(E, &str)],
+) -> Result<E, MappingError> {
+    check_not_null(object, class_type.get_name())?;
+
+    let objects = mapping.cache.enum_constants(class_type, || {
+        constants
+            .iter()
+            .map(|(_, field_name)| {
+                let constant = constant_object(mapping, class_type, field_name)?;
+                convert_local(mapping, constant, GlobalRef::from_java)
+            })
+            .collect()
+    })?;
+
+    let env = mapping.try_get_env()?;
+    for ((value, _), constant) in constants.iter().zip(objects.iter()) {
+        if env.is_same_object(object, constant)? {
+            return Ok(*value);
+        }
+    }
+
+    let name = call_method(mapping, object, "name", "()Ljava/lang/String;", &[])?.l()?;
+    let name = convert_local(mapping, name, String::from_java)?;
+    Err(MappingError::InvalidValue {
+        class: class_type.get_name().to_string(),
+        message: format!("unknown constant {}", name),
+    })
+}
@@ -1,9 +1,129 @@
-use jni::objects::GlobalRef;
+use crate::mapping::error::MappingError;
+use crate::mapping::Mapping;
+use jni::objects::{GlobalRef, JObject, JString, JValue, JValueOwned};
 
-pub struct JavaList {
-    pub jni_list: GlobalRef,
+pub mod boxed;
+pub mod collection;
+pub mod enums;
+pub mod optional;
+pub mod uuid;
+
+pub use collection::{JavaIterator, JavaList, JavaMap, JavaSet};
+pub use optional::Optional;
+pub use uuid::Uuid;
+
+/// Rust value read from a Java object, used for the elements of the collection wrappers
+///
+/// The object is a local reference owned by the caller, a value keeping it must create its own global reference.
+pub trait FromJava: Sized {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError>;
+}
+
+/// Java object created from a Rust value, the inverse of [`FromJava`]
+///
+/// The returned object is a new local reference, the caller frees it once it is passed to Java.
+pub trait IntoJava {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError>;
+}
+
+/// `null` becomes `None`, any other object is converted as `T`
+impl<T: FromJava> FromJava for Option<T> {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        if object.is_null() {
+            return Ok(None);
+        }
+        T::from_java(mapping, object).map(Some)
+    }
+}
+
+/// `None` becomes `null`
+impl<T: IntoJava> IntoJava for Option<T> {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+        match self {
+            Some(value) => value.to_java(mapping),
+            None => Ok(JObject::null()),
+        }
+    }
+}
+
+impl FromJava for GlobalRef {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        check_not_null(object, "GlobalRef")?;
+        let mut env = mapping.try_get_env()?;
+        env.new_global_ref(object)
+            .map_err(|e| mapping.java_error(&mut env, e))
+    }
+}
+
+impl FromJava for String {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        check_not_null(object, "String")?;
+        let mut env = mapping.try_get_env()?;
+        env.get_string(<&JString>::from(object))
+            .map(String::from)
+            .map_err(|e| mapping.java_error(&mut env, e))
+    }
+}
+
+impl IntoJava for String {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+        self.as_str().to_java(mapping)
+    }
+}
+
+impl IntoJava for &str {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+        let mut env = mapping.try_get_env()?;
+        env.new_string(self)
+            .map(JObject::from)
+            .map_err(|e| mapping.java_error(&mut env, e))
+    }
+}
+
+/// Fails on `null`, which can only be read as an `Option`
+pub(crate) fn check_not_null(
+    object: &JObject,
+    type_name: &'static str,
+) -> Result<(), MappingError> {
+    if object.is_null() {
+        return Err(MappingError::Jni(jni::errors::Error::NullPtr(type_name)));
+    }
+    Ok(())
+}
+
+/// Calls a method of a JDK interface like `java.util.List`, whose names are never obfuscated
+fn call_method<'a>(
+    mapping: &'a Mapping,
+    object: &JObject,
+    name: &str,
+    signature: &str,
+    args: &[JValue],
+) -> Result<JValueOwned<'a>, MappingError> {
+    let mut env = mapping.try_get_env()?;
+    env.call_method(object, name, signature, args)
+        .map_err(|e| mapping.java_error(&mut env, e))
+}
+
+/// Calls a static method of a JDK class, e.g. `Integer.valueOf`
+fn call_static_method<'a>(
+    mapping: &'a Mapping,
+    class: &str,
+    name: &str,
+    signature: &str,
+    args: &[JValue],
+) -> Result<JValueOwned<'a>, MappingError> {
+    let mut env = mapping.try_get_env()?;
+    env.call_static_method(class, name, signature, args)
+        .map_err(|e| mapping.java_error(&mut env, e))
 }
 
-pub struct JavaSet {
-    pub jni_set: GlobalRef,
+/// Converts an object returned by a Java call and frees its local reference, even if the conversion fails
+pub(crate) fn convert_local<T>(
+    mapping: &Mapping,
+    object: JObject,
+    convert: impl FnOnce(&Mapping, &JObject) -> Result<T, MappingError>,
+) -> Result<T, MappingError> {
+    let value = convert(mapping, &object);
+    mapping.try_get_env()?.delete_local_ref(object)?;
+    value
 }
@@ -0,0 +1,60 @@
+use crate::mapping::error::MappingError;
+use crate::mapping::java::{
+    call_method, call_static_method, check_not_null, convert_local, FromJava, IntoJava,
+};
+use crate::mapping::Mapping;
+use jni::objects::{JObject, JValue};
+
+/// `java.util.Optional`, unlike `Option<T>` which reads a nullable reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct Optional<T>(pub Option<T>);
+
+impl<T> From<Optional<T>> for Option<T> {
+    fn from(optional: Optional<T>) -> Self {
+        optional.0
+    }
+}
+
+impl<T> From<Option<T>> for Optional<T> {
+    fn from(option: Option<T>) -> Self {
+        Optional(option)
+    }
+}
+
+impl<T: FromJava> FromJava for Optional<T> {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        check_not_null(object, "java/util/Optional")?;
+        if !call_method(mapping, object, "isPresent", "()Z", &[])?.z()? {
+            return Ok(Optional(None));
+        }
+
+        let value = call_method(mapping, object, "get", "()Ljava/lang/Object;", &[])?.l()?;
+        convert_local(mapping, value, T::from_java).map(|value| Optional(Some(value)))
+    }
+}
+
+impl<T: IntoJava> IntoJava for Optional<T> {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+        let Some(value) = &self.0 else {
+            return Ok(call_static_method(
+                mapping,
+                "java/util/Optional",
+                "empty",
+                "()Ljava/util/Optional;",
+                &[],
+            )?
+            .l()?);
+        };
+
+        let value = value.to_java(mapping)?;
+        let optional = call_static_method(
+            mapping,
+            "java/util/Optional",
+            "of",
+            "(Ljava/lang/Object;)Ljava/util/Optional;",
+            &[JValue::Object(&value)],
+        );
+        mapping.try_get_env()?.delete_local_ref(value)?;
+        Ok(optional?.l()?)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::mapping::error::MappingError;
+use crate::mapping::java::{call_method, check_not_null, FromJava, IntoJava};
+use crate::mapping::Mapping;
+use jni::objects::{JObject, JValue};
+use std::fmt;
+
+/// `java.util.UUID`, e.g. the UUID of an entity or of a player profile
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Uuid(pub u128);
+
+impl Uuid {
+    /// Builds the UUID from the two halves returned by `getMostSignificantBits` and `getLeastSignificantBits`
+    pub fn from_bits(most_significant_bits: i64, least_significant_bits: i64) -> Self {
+        Uuid(((most_significant_bits as u64 as u128) << 64) | least_significant_bits as u64 as u128)
+    }
+
+    pub fn most_significant_bits(&self) -> i64 {
+        (self.0 >> 64) as u64 as i64
+    }
+
+    pub fn least_significant_bits(&self) -> i64 {
+        self.0 as u64 as i64
+    }
+}
+
+/// Formats like `UUID.toString()`, e.g. `069a79f4-44e9-4726-a5be-fca90e38aaf5`
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            self.0 >> 96,
+            (self.0 >> 80) & 0xffff,
+            (self.0 >> 64) & 0xffff,
+            (self.0 >> 48) & 0xffff,
+            self.0 & 0xffff_ffff_ffff
+        )
+    }
+}
+
+impl FromJava for Uuid {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        check_not_null(object, "java/util/UUID")?;
+        let most_significant_bits =
+            call_method(mapping, object, "getMostSignificantBits", "()J", &[])?.j()?;
+        let least_significant_bits =
+            call_method(mapping, object, "getLeastSignificantBits", "()J", &[])?.j()?;
+        Ok(Uuid::from_bits(
+            most_significant_bits,
+            least_significant_bits,
+        ))
+    }
+}
+
+impl IntoJava for Uuid {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+        let mut env = mapping.try_get_env()?;
+        env.new_object(
+            "java/util/UUID",
+            "(JJ)V",
+            &[
+                JValue::Long(self.most_significant_bits()),
+                JValue::Long(self.least_significant_bits()),
+            ],
+        )
+        .map_err(|e| mapping.java_error(&mut env, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uuid_bits() {
+        // Bits of UUID.fromString("069a79f4-44e9-4726-a5be-fca90e38aaf5")
+        let uuid = Uuid::from_bits(475826800676128550, -6503483008858150155);
+        assert_eq!(uuid.to_string(), "069a79f4-44e9-4726-a5be-fca90e38aaf5");
+        assert_eq!(uuid.most_significant_bits(), 475826800676128550);
+        assert_eq!(uuid.least_significant_bits(), -6503483008858150155);
+
+        let negative = Uuid::from_bits(-1, 1);
+        assert_eq!(negative.to_string(), "ffffffff-ffff-ffff-0000-000000000001");
+        assert_eq!(negative.most_significant_bits(), -1);
+    }
+}
@@ -0,0 +1,104 @@
+//! Helpers on JNI descriptors without dependencies on the rest of the client
+//!
+//! Also compiled into `build.rs`, which remaps and splits the descriptors of the generated bindings with them.
+
+/// Rewrites the class names of a JNI descriptor, classes without a replacement are kept as is
+///
+/// # Example
+/// `(Lfzz;[Lfby;I)V` -> `(Lnet/minecraft/class_310;[Lnet/minecraft/class_243;I)V`
+pub fn remap_descriptor<F, S>(descriptor: &str, mut remap: F) -> String
+where
+    F: FnMut(&str) -> Option<S>,
+    S: AsRef<str>,
+{
+    let mut remapped = String::with_capacity(descriptor.len());
+    let mut rest = descriptor;
+
+    while let Some(start) = rest.find('L') {
+        let Some(end) = rest[start..].find(';').map(|end| start + end) else {
+            break;
+        };
+
+        let class_name = &rest[start + 1..end];
+        remapped.push_str(&rest[..=start]);
+        match remap(class_name) {
+            Some(new_name) => remapped.push_str(new_name.as_ref()),
+            None => remapped.push_str(class_name),
+        }
+        remapped.push(';');
+        rest = &rest[end + 1..];
+    }
+
+    remapped.push_str(rest);
+    remapped
+}
+
+/// Splits the parameters of a method descriptor, `None` if it is malformed
+///
+/// # Example
+/// `(I[[Lfzz;Lfzz$a;)V` -> `["I", "[[Lfzz;", "Lfzz$a;"]`
+pub fn parse_parameters(descriptor: &str) -> Option<Vec<&str>> {
+    let parameters = descriptor.strip_prefix('(')?.split_once(')')?.0;
+    let mut result = Vec::new();
+    let mut start = 0;
+    let bytes = parameters.as_bytes();
+
+    while start < bytes.len() {
+        let mut end = start;
+        while bytes[end] == b'[' {
+            end += 1;
+            if end >= bytes.len() {
+                return None;
+            }
+        }
+        if bytes[end] == b'L' {
+            end += parameters[end..].find(';')?;
+        }
+        result.push(&parameters[start..=end]);
+        start = end + 1;
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_remap_descriptor() {
+        let classes = HashMap::from([
+            ("fzz", "net/minecraft/class_310"),
+            ("fby", "net/minecraft/class_243"),
+            ("fzz$a", "net/minecraft/class_310$class_311"),
+        ]);
+        let remap = |name: &str| classes.get(name).copied();
+
+        assert_eq!(remap_descriptor("()V", remap), "()V");
+        assert_eq!(
+            remap_descriptor("(Lfzz;[Lfby;I)Ljava/lang/String;", remap),
+            "(Lnet/minecraft/class_310;[Lnet/minecraft/class_243;I)Ljava/lang/String;"
+        );
+        assert_eq!(
+            remap_descriptor("[[Lfby;", remap),
+            "[[Lnet/minecraft/class_243;"
+        );
+        assert_eq!(
+            remap_descriptor("(JLfzz$a;)Z", remap),
+            "(JLnet/minecraft/class_310$class_311;)Z"
+        );
+    }
+
+    #[test]
+    fn test_parse_parameters() {
+        assert_eq!(parse_parameters("()V"), Some(vec![]));
+        assert_eq!(
+            parse_parameters("(I[[Lfzz;Lfzz$a;[J)V"),
+            Some(vec!["I", "[[Lfzz;", "Lfzz$a;", "[J"])
+        );
+        assert_eq!(parse_parameters("(Lfzz)V"), None);
+        assert_eq!(parse_parameters("(I[)V"), None);
+        assert_eq!(parse_parameters("V"), None);
+    }
+}
@@ -0,0 +1,502 @@
+use crate::mapping::error::MappingError;
+use crate::mapping::namespace::Namespace;
+use crate::mapping::overlay::Overlay;
+use crate::mapping::packed::{is_packed, PackedError};
+use crate::mapping::tiny::TinyMapping;
+use crate::mapping::Mapping;
+use log::{error, info, warn};
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Environment variable that can point to a mapping file
+pub const MAPPINGS_ENV: &str = "DARK_CLIENT_MAPPINGS";
+
+/// Environment variable that can point to a directory of per-version mapping files
+pub const MAPPINGS_DIR_ENV: &str = "DARK_CLIENT_MAPPINGS_DIR";
+
+/// Environment variable with a list of overlay files, separated like `PATH`
+pub const OVERLAYS_ENV: &str = "DARK_CLIENT_OVERLAYS";
+
+/// Environment variable that can point to a Tiny v2 file with official and intermediary names
+pub const INTERMEDIARY_ENV: &str = "DARK_CLIENT_INTERMEDIARY";
+
+/// Config file read from the working directory, next to `dark_client.log`
+pub const CONFIG_FILE: &str = "dark_client.json";
+
+/// Directory of per-version mapping files (`<version>.bin`, `<version>.json` and `<version>.tiny`) used when
+/// none is configured
+pub const DEFAULT_MAPPINGS_DIR: &str = "mappings";
+
+/// Mappings compiled into the library, used when no other source is available
+///
+/// `build.rs` packs the `mappings.json` of the project root, see [`Mapping::from_packed`].
+const EMBEDDED_MAPPINGS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/mappings.bin"));
+
+/// Path received from the agent's reload command, takes precedence over every other source
+static AGENT_MAPPING_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub fn set_agent_mapping_path(path: PathBuf) {
+    *AGENT_MAPPING_PATH.lock().unwrap() = Some(path);
+}
+
+/// Where a mapping was loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MappingSource {
+    Agent(PathBuf),
+    Environment(PathBuf),
+    Config(PathBuf),
+    Directory(PathBuf),
+    Embedded,
+}
+
+impl fmt::Display for MappingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingSource::Agent(path) => write!(f, "agent reload command ({})", path.display()),
+            MappingSource::Environment(path) => {
+                write!(
+                    f,
+                    "{} environment variable ({})",
+                    MAPPINGS_ENV,
+                    path.display()
+                )
+            }
+            MappingSource::Config(path) => write!(f, "{} ({})", CONFIG_FILE, path.display()),
+            MappingSource::Directory(path) => write!(f, "mapping directory ({})", path.display()),
+            MappingSource::Embedded => write!(f, "embedded mapping"),
+        }
+    }
+}
+
+/// Error raised while reading the config file or a mapping file
+#[derive(Debug)]
+pub enum MappingLoadError {
+    Config(PathBuf, String),
+    Io(MappingSource, std::io::Error),
+    Parse(MappingSource, serde_json::Error),
+    Packed(MappingSource, PackedError),
+    VersionMismatch {
+        source: MappingSource,
+        mapping_version: String,
+        game_version: String,
+    },
+    /// The mapping declares no version and [`ClientConfig::allow_unversioned`] isn't set
+    Unversioned {
+        source: MappingSource,
+        game_version: String,
+    },
+    NoMatchingMapping(Option<String>),
+    Tiny(PathBuf, String),
+    MissingTiny(Namespace),
+    Overlay(PathBuf, String),
+    /// The mapping was loaded, but the game objects needed at startup couldn't be resolved
+    Resolve(MappingError),
+}
+
+impl fmt::Display for MappingLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MappingLoadError::Config(path, e) => {
+                write!(f, "invalid config file {}: {}", path.display(), e)
+            }
+            MappingLoadError::Io(source, e) => write!(f, "unable to read {}: {}", source, e),
+            MappingLoadError::Parse(source, e) => write!(f, "unable to parse {}: {}", source, e),
+            MappingLoadError::Packed(source, e) => {
+                write!(f, "unable to read packed {}: {}", source, e)
+            }
+            MappingLoadError::VersionMismatch {
+                source,
+                mapping_version,
+                game_version,
+            } => write!(
+                f,
+                "{} is for Minecraft {}, but the game is running {}",
+                source, mapping_version, game_version
+            ),
+            MappingLoadError::Unversioned {
+                source,
+                game_version,
+            } => write!(
+                f,
+                "{} does not declare a version, set allow_unversioned in {} to use it on Minecraft {}",
+                source, CONFIG_FILE, game_version
+            ),
+            MappingLoadError::NoMatchingMapping(Some(version)) => {
+                write!(f, "no mapping available for Minecraft {}", version)
+            }
+            MappingLoadError::NoMatchingMapping(None) => write!(f, "no usable mapping available"),
+            MappingLoadError::Tiny(path, e) => {
+                write!(f, "unable to load Tiny mapping {}: {}", path.display(), e)
+            }
+            MappingLoadError::MissingTiny(namespace) => write!(
+                f,
+                "the game uses {} names but no Tiny mapping is configured, set {} or the intermediary entry of {}",
+                namespace, INTERMEDIARY_ENV, CONFIG_FILE
+            ),
+            MappingLoadError::Overlay(path, e) => {
+                write!(f, "unable to apply overlay {}: {}", path.display(), e)
+            }
+            MappingLoadError::Resolve(e) => write!(f, "unable to resolve the game objects: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for MappingLoadError {}
+
+impl From<MappingError> for MappingLoadError {
+    fn from(error: MappingError) -> Self {
+        MappingLoadError::Resolve(error)
+    }
+}
+
+/// Client configuration read from [`CONFIG_FILE`]
+#[derive(Debug, Default, Deserialize)]
+pub struct ClientConfig {
+    /// Mapping file, relative paths are resolved against the config file directory
+    pub mappings: Option<PathBuf>,
+    /// Directory of per-version mapping files, resolved like `mappings`
+    pub mappings_dir: Option<PathBuf>,
+    /// Tiny v2 file used to remap to the intermediary namespace, resolved like `mappings`
+    pub intermediary: Option<PathBuf>,
+    /// Runtime namespace, detected from the loaded classes if missing
+    pub namespace: Option<Namespace>,
+    /// Overlay files merged into the mapping in order, resolved like `mappings`
+    #[serde(default)]
+    pub overlays: Vec<PathBuf>,
+    /// Accepts mappings without a version on any detected game version, e.g. old files converted without
+    /// `--version`
+    #[serde(default)]
+    pub allow_unversioned: bool,
+}
+
+impl ClientConfig {
+    pub fn read(path: &Path) -> Result<Option<Self>, MappingLoadError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(MappingLoadError::Config(path.to_path_buf(), e.to_string())),
+        };
+
+        let mut config: ClientConfig = serde_json::from_str(&contents)
+            .map_err(|e| MappingLoadError::Config(path.to_path_buf(), e.to_string()))?;
+
+        if let Some(dir) = path.parent() {
+            for path in [
+                &mut config.mappings,
+                &mut config.mappings_dir,
+                &mut config.intermediary,
+            ]
+            .into_iter()
+            .flatten()
+            .chain(&mut config.overlays)
+            {
+                if path.is_relative() {
+                    *path = dir.join(&*path);
+                }
+            }
+        }
+
+        Ok(Some(config))
+    }
+}
+
+/// Returns the configured mapping sources in priority order, the embedded copy is always last
+///
+/// When the game version is known, the `<version>.bin` file of the mapping directory, or `<version>.json`
+/// if there is none, is tried after the explicitly configured files.
+pub fn configured_sources(version: Option<&str>) -> Vec<MappingSource> {
+    let mut sources = Vec::new();
+
+    if let Some(path) = AGENT_MAPPING_PATH.lock().unwrap().clone() {
+        sources.push(MappingSource::Agent(path));
+    }
+
+    if let Some(path) = env_path(MAPPINGS_ENV) {
+        sources.push(MappingSource::Environment(path));
+    }
+
+    let config = read_config();
+
+    if let Some(path) = &config.mappings {
+        sources.push(MappingSource::Config(path.clone()));
+    }
+
+    if let Some(version) = version {
+        let directory = mappings_directory(&config);
+        if directory.is_dir() {
+            let packed = directory.join(format!("{}.bin", version));
+            sources.push(MappingSource::Directory(if packed.is_file() {
+                packed
+            } else {
+                directory.join(format!("{}.json", version))
+            }));
+        }
+    }
+
+    sources.push(MappingSource::Embedded);
+    sources
+}
+
+/// Returns the overlay files in the order they are applied, a later file overrides an earlier one
+///
+/// The files of the config come first, then the ones of the environment variable.
+pub fn configured_overlays() -> Vec<PathBuf> {
+    let mut overlays = read_config().overlays;
+    if let Some(paths) = std::env::var_os(OVERLAYS_ENV) {
+        overlays.extend(std::env::split_paths(&paths).filter(|path| !path.as_os_str().is_empty()));
+    }
+    overlays
+}
+
+/// Reads [`CONFIG_FILE`], an invalid config is logged and ignored
+fn read_config() -> ClientConfig {
+    ClientConfig::read(Path::new(CONFIG_FILE))
+        .unwrap_or_else(|e| {
+            error!("{}", e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+fn mappings_directory(config: &ClientConfig) -> PathBuf {
+    env_path(MAPPINGS_DIR_ENV)
+        .or_else(|| config.mappings_dir.clone())
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_MAPPINGS_DIR))
+}
+
+/// Returns the Tiny file to remap with, `<version>.tiny` of the mapping directory if none is configured
+fn intermediary_path(config: &ClientConfig, version: Option<&str>) -> Option<PathBuf> {
+    env_path(INTERMEDIARY_ENV)
+        .or_else(|| config.intermediary.clone())
+        .or_else(|| {
+            let path = mappings_directory(config).join(format!("{}.tiny", version?));
+            path.is_file().then_some(path)
+        })
+}
+
+impl Mapping {
+    /// Loads the mapping from the first source that succeeds and matches the game version,
+    /// falling back to the embedded copy
+    pub fn load(version: Option<&str>) -> Result<(Mapping, MappingSource), MappingLoadError> {
+        let allow_unversioned = read_config().allow_unversioned;
+        for source in configured_sources(version) {
+            match Self::load_from(&source)
+                .and_then(|mapping| mapping.check_version(&source, version, allow_unversioned))
+            {
+                Ok(mapping) => {
+                    info!("Mappings loaded from {}", source);
+                    return Ok((mapping, source));
+                }
+                Err(e) => error!("Failed to load mappings: {}", e),
+            }
+        }
+
+        Err(MappingLoadError::NoMatchingMapping(
+            version.map(String::from),
+        ))
+    }
+
+    /// Remaps the runtime names to the namespace used by the game, configured or detected
+    pub fn select_namespace(&mut self, version: Option<&str>) -> Result<(), MappingLoadError> {
+        let config = read_config();
+        let namespace = config.namespace.unwrap_or_else(Namespace::detect);
+        info!("Game uses {} names", namespace);
+
+        if namespace == self.namespace {
+            return Ok(());
+        }
+
+        let path =
+            intermediary_path(&config, version).ok_or(MappingLoadError::MissingTiny(namespace))?;
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| MappingLoadError::Tiny(path.clone(), e.to_string()))?;
+        let tiny = TinyMapping::parse(&contents)
+            .map_err(|e| MappingLoadError::Tiny(path.clone(), e.to_string()))?;
+
+        self.remap_namespace(&tiny, namespace)
+            .map_err(|e| MappingLoadError::Tiny(path.clone(), e.to_string()))?;
+        info!(
+            "Mappings remapped to {} names using {}",
+            namespace,
+            path.display()
+        );
+        Ok(())
+    }
+
+    /// Merges the configured overlays, see [`configured_overlays`], logging every entry they change
+    ///
+    /// An overlay that can't be read or is for another game version is skipped.
+    pub fn apply_overlays(&mut self, version: Option<&str>) {
+        for path in configured_overlays() {
+            let changes = Self::read_overlay(&path, version).and_then(|overlay| {
+                self.apply_overlay(overlay)
+                    .map_err(|e| MappingLoadError::Overlay(path.clone(), e))
+            });
+            match changes {
+                Ok(changes) => {
+                    for change in &changes {
+                        info!("Overlay {}: {}", path.display(), change);
+                    }
+                    info!(
+                        "Overlay {} applied, {} changes",
+                        path.display(),
+                        changes.len()
+                    );
+                }
+                Err(e) => error!("{}", e),
+            }
+        }
+    }
+
+    fn read_overlay(path: &Path, game_version: Option<&str>) -> Result<Overlay, MappingLoadError> {
+        let error = |e: String| MappingLoadError::Overlay(path.to_path_buf(), e);
+        let contents = fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+        let overlay: Overlay = serde_json::from_str(&contents).map_err(|e| error(e.to_string()))?;
+
+        match (&overlay.version, game_version) {
+            (Some(overlay_version), Some(game_version)) if overlay_version != game_version => {
+                Err(error(format!(
+                    "it is for Minecraft {}, but the game is running {}",
+                    overlay_version, game_version
+                )))
+            }
+            _ => Ok(overlay),
+        }
+    }
+
+    /// Rejects the mapping if it declares a version different from the game one, or none unless
+    /// `allow_unversioned` is set
+    fn check_version(
+        self,
+        source: &MappingSource,
+        game_version: Option<&str>,
+        allow_unversioned: bool,
+    ) -> Result<Mapping, MappingLoadError> {
+        match (&self.version, game_version) {
+            (Some(mapping_version), Some(game_version)) if mapping_version != game_version => {
+                Err(MappingLoadError::VersionMismatch {
+                    source: source.clone(),
+                    mapping_version: mapping_version.clone(),
+                    game_version: game_version.to_string(),
+                })
+            }
+            (None, Some(game_version)) if !allow_unversioned => {
+                Err(MappingLoadError::Unversioned {
+                    source: source.clone(),
+                    game_version: game_version.to_string(),
+                })
+            }
+            (None, Some(game_version)) => {
+                warn!(
+                    "{} does not declare a version, assuming it matches Minecraft {}",
+                    source, game_version
+                );
+                Ok(self)
+            }
+            _ => Ok(self),
+        }
+    }
+
+    /// Reads a mapping file, packed or JSON, see [`Mapping::from_packed`] and [`Mapping::from_json`]
+    pub fn load_from(source: &MappingSource) -> Result<Mapping, MappingLoadError> {
+        let contents = match source {
+            MappingSource::Agent(path)
+            | MappingSource::Environment(path)
+            | MappingSource::Config(path)
+            | MappingSource::Directory(path) => {
+                fs::read(path).map_err(|e| MappingLoadError::Io(source.clone(), e))?
+            }
+            MappingSource::Embedded => {
+                return Self::from_packed(EMBEDDED_MAPPINGS)
+                    .map_err(|e| MappingLoadError::Packed(source.clone(), e));
+            }
+        };
+
+        if is_packed(&contents) {
+            Self::from_packed(contents).map_err(|e| MappingLoadError::Packed(source.clone(), e))
+        } else {
+            Self::from_json(&contents).map_err(|e| MappingLoadError::Parse(source.clone(), e))
+        }
+    }
+
+    /// Parses a mapping in the JSON format, with its descriptors rewritten to runtime names
+    pub fn from_json(contents: impl AsRef<[u8]>) -> Result<Mapping, serde_json::Error> {
+        let mut mapping: Mapping = serde_json::from_slice(contents.as_ref())?;
+        mapping.resolve_descriptors();
+        Ok(mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_relative_path() {
+        let dir = std::env::temp_dir().join("dark_client_config_test");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_FILE);
+        fs::write(
+            &path,
+            r#"{"mappings": "mappings/1.21.5.json", "mappings_dir": "/opt/mappings", "overlays": ["fix.json"]}"#,
+        )
+        .unwrap();
+
+        let config = ClientConfig::read(&path).unwrap().unwrap();
+        assert_eq!(config.mappings, Some(dir.join("mappings/1.21.5.json")));
+        assert_eq!(config.mappings_dir, Some(PathBuf::from("/opt/mappings")));
+        assert_eq!(config.overlays, vec![dir.join("fix.json")]);
+        assert!(!config.allow_unversioned);
+
+        assert!(ClientConfig::read(&dir.join("missing.json"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_missing_file_names_source() {
+        let source = MappingSource::Environment(PathBuf::from("/nonexistent/mappings.json"));
+        let error = Mapping::load_from(&source).unwrap_err();
+        assert!(error.to_string().contains(MAPPINGS_ENV));
+        assert!(error.to_string().contains("/nonexistent/mappings.json"));
+    }
+
+    #[test]
+    fn test_version_check() {
+        let source = MappingSource::Embedded;
+        let mapping: Mapping =
+            serde_json::from_str(r#"{"version": "1.21.5", "classes": {}}"#).unwrap();
+        let mapping = mapping
+            .check_version(&source, Some("1.21.5"), false)
+            .unwrap();
+
+        let error = mapping
+            .check_version(&source, Some("1.21.6"), true)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "embedded mapping is for Minecraft 1.21.5, but the game is running 1.21.6"
+        );
+
+        let mapping: Mapping = serde_json::from_str(r#"{"classes": {}}"#).unwrap();
+        let error = mapping
+            .check_version(&source, Some("1.21.6"), false)
+            .unwrap_err();
+        assert!(matches!(error, MappingLoadError::Unversioned { .. }));
+
+        let mapping: Mapping = serde_json::from_str(r#"{"classes": {}}"#).unwrap();
+        let mapping = mapping
+            .check_version(&source, Some("1.21.6"), true)
+            .unwrap();
+        assert!(mapping.check_version(&source, None, false).is_ok());
+    }
+}
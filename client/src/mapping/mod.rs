@@ -1,16 +1,50 @@
 use crate::client::DarkClient;
+use crate::mapping::cache::ResolutionCache;
 use crate::mapping::client::minecraft::Minecraft;
+use crate::mapping::descriptor::{
+    descriptor_to_java_type, remap_descriptor, DescriptorNames, MethodSelector,
+};
+use crate::mapping::error::MappingError;
+use crate::mapping::hierarchy::DiscoveredHierarchy;
+use crate::mapping::loader::MappingLoadError;
+use crate::mapping::namespace::Namespace;
+use crate::mapping::packed::PackedClasses;
+use crate::mapping::reverse::ReverseIndex;
 use crate::LogExpect;
 use jni::objects::{GlobalRef, JClass, JObject, JString, JValue, JValueOwned};
+use jni::sys::jvalue;
 use jni::JNIEnv;
 use serde::de::{MapAccess, Visitor};
-use serde::{Deserialize, Deserializer};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt;
-
+use std::sync::OnceLock;
+
+pub mod array;
+pub mod bindings;
+pub mod cache;
+pub mod chat;
+pub mod check;
+pub mod class_loader;
 pub mod client;
+pub mod descriptor;
+pub mod diff;
 pub mod entity;
+pub mod error;
+pub mod hierarchy;
 pub mod java;
+pub mod jni_descriptor;
+pub mod loader;
+pub mod namespace;
+pub mod overlay;
+pub mod packed;
+pub mod phys;
+pub mod proguard;
+pub mod reflection;
+pub mod reverse;
+pub mod tiny;
+pub mod version;
 
 pub trait GameContext {
     fn client(&self) -> &'static DarkClient {
@@ -65,6 +99,27 @@ where
     deserializer.deserialize_map(MethodsVisitor)
 }
 
+/// Serializes methods without overloads as a single Method, mirroring [`deserialize_methods`]
+fn serialize_methods<S>(
+    methods: &HashMap<String, Vec<Method>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut map = serializer.serialize_map(Some(methods.len()))?;
+    for (name, overloads) in methods {
+        match overloads.as_slice() {
+            [method] => map.serialize_entry(name, method)?,
+            _ => map.serialize_entry(name, overloads)?,
+        }
+    }
+    map.end()
+}
+
+/// Name of the constructors in the mapping, as in the JVM
+pub const CONSTRUCTOR: &str = "<init>";
+
 /// Helper enum for deserializing either a single Method or Vec<Method>
 #[derive(Deserialize)]
 #[serde(untagged)]
@@ -74,31 +129,62 @@ enum MethodOrVec {
 }
 
 /// Root structure containing all mapped Minecraft classes
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Mapping {
+    /// Game version the mapping was generated for, unknown if missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    /// Namespace of the runtime names, the keys are always Mojang names
+    #[serde(default)]
+    namespace: Namespace,
+    /// Names used by the descriptors in the file, always runtime names once loaded
+    #[serde(default, skip_serializing_if = "DescriptorNames::is_runtime")]
+    descriptors: DescriptorNames,
     classes: HashMap<String, MinecraftClass>,
+    /// Classes of a packed mapping not decoded yet, see [`Mapping::from_packed`]
+    #[serde(skip)]
+    packed: Option<PackedClasses>,
+    #[serde(skip)]
+    cache: ResolutionCache,
+    #[serde(skip)]
+    reverse: OnceLock<ReverseIndex>,
+    #[serde(skip)]
+    discovered: DiscoveredHierarchy,
 }
 
 /// Represents a mapped Minecraft class with its methods and fields
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct MinecraftClass {
     name: String,
-    #[serde(deserialize_with = "deserialize_methods", default)]
+    #[serde(
+        deserialize_with = "deserialize_methods",
+        serialize_with = "serialize_methods",
+        default
+    )]
     methods: HashMap<String, Vec<Method>>,
     fields: HashMap<String, Field>,
+    /// Mojang name of the superclass, missing for `java/lang/Object` or if unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    superclass: Option<String>,
+    /// Mojang names of the directly implemented interfaces
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    interfaces: Vec<String>,
 }
 
 /// Represents a method with its obfuscated name and JNI signature
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Method {
     name: String,
     signature: String,
 }
 
-/// Represents a field with its obfuscated name
-#[derive(Debug, Deserialize)]
+/// Represents a field with its obfuscated name and JNI type descriptor
+#[derive(Debug, Deserialize, Serialize)]
 pub struct Field {
     name: String,
+    /// Missing in mappings generated before field types were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
 }
 
 /// Signature matching result for method resolution
@@ -110,20 +196,26 @@ enum SignatureMatch {
 }
 
 impl MinecraftClass {
+    /// Name of the class in the running game, e.g. `fzz`
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
     pub fn get_method(&self, name: &str) -> &Method {
-        self.methods
-            .get(name)
-            .unwrap()
-            .first()
+        self.find_methods(name)
+            .and_then(|methods| methods.first())
             .log_expect(format!("{} method not found", name).as_str())
     }
 
     pub fn get_methods(&self, name: &str) -> &Vec<Method> {
-        self.methods
-            .get(name)
+        self.find_methods(name)
             .log_expect(format!("{} method not found", name).as_str())
     }
 
+    pub fn find_methods(&self, name: &str) -> Option<&Vec<Method>> {
+        self.methods.get(name)
+    }
+
     pub fn get_method_by_signature(&self, name: &str, signature: &str) -> &Method {
         let methods = self.get_methods(name);
         methods
@@ -133,49 +225,53 @@ impl MinecraftClass {
     }
 
     pub fn get_method_by_args(&self, name: &str, args: &[JValue]) -> &Method {
-        let methods = self.get_methods(name);
+        self.find_method_by_args(name, args).log_expect(
+            format!("{} method not found or ambiguous for the arguments", name).as_str(),
+        )
+    }
+
+    /// Returns the overload that matches `args`, `None` if no overload or more than one matches
+    pub fn find_method_by_args(&self, name: &str, args: &[JValue]) -> Option<&Method> {
+        let methods: Vec<&Method> = self.find_methods(name)?.iter().collect();
 
         // If only one method exists, return it immediately
         if methods.len() == 1 {
-            return &methods[0];
+            return Some(methods[0]);
         }
 
-        // Find the best matching method based on argument compatibility
-        let mut best_method = None;
-        let mut best_match_quality = SignatureMatch::Incompatible;
-
-        for method in methods {
-            let match_quality = self.evaluate_signature_compatibility(&method.signature, args);
-
-            if match_quality == SignatureMatch::Exact {
-                // Exact match found, return immediately
-                return method;
+        match self.matching_overloads(&methods, args).as_slice() {
+            [method] => Some(method),
+            matching => {
+                log::warn!(
+                    "{} overloads of '{}' match {} arguments",
+                    matching.len(),
+                    name,
+                    args.len()
+                );
+                None
             }
+        }
+    }
 
-            if match_quality == SignatureMatch::Compatible
-                && best_match_quality != SignatureMatch::Exact
-            {
-                best_method = Some(method);
-                best_match_quality = match_quality;
+    /// Returns the overloads matching `args` exactly, or the compatible ones if none does
+    ///
+    /// More than one result means the call is ambiguous, e.g. a null argument for two object parameters.
+    fn matching_overloads<'a>(&self, methods: &[&'a Method], args: &[JValue]) -> Vec<&'a Method> {
+        let mut exact = Vec::new();
+        let mut compatible = Vec::new();
+
+        for method in methods {
+            match self.evaluate_signature_compatibility(&method.signature, args) {
+                SignatureMatch::Exact => exact.push(*method),
+                SignatureMatch::Compatible => compatible.push(*method),
+                SignatureMatch::Incompatible => {}
             }
         }
 
-        match best_method {
-            Some(method) => {
-                log::debug!(
-                    "Using compatible method '{}' with signature '{}' for args",
-                    name,
-                    method.signature
-                );
-                method
-            }
-            None => {
-                log::warn!(
-                    "No compatible method found for '{}' with {} arguments, using first available method",
-                    name, args.len()
-                );
-                &methods[0]
-            }
+        if exact.is_empty() {
+            compatible
+        } else {
+            exact
         }
     }
 
@@ -249,7 +345,7 @@ impl MinecraftClass {
                 // Object types
                 'L' => {
                     let mut object_type = String::from("L");
-                    while let Some(ch) = chars.next() {
+                    for ch in chars.by_ref() {
                         object_type.push(ch);
                         if ch == ';' {
                             break;
@@ -266,7 +362,7 @@ impl MinecraftClass {
                                 array_type.push(chars.next().unwrap());
                             }
                             'L' => {
-                                while let Some(ch) = chars.next() {
+                                for ch in chars.by_ref() {
                                     array_type.push(ch);
                                     if ch == ';' {
                                         break;
@@ -367,8 +463,13 @@ impl MinecraftClass {
         if let Ok(mut env) = DarkClient::instance().get_env() {
             // Get the actual class of the object
             if let Ok(obj_class) = env.get_object_class(obj) {
-                // Check for exact class match first
-                if let Ok(expected_class) = env.find_class(expected_class_name) {
+                // Check for exact class match first, the expected class is loaded through the
+                // class loader of the object as game classes aren't visible to find_class
+                let expected_class = load_class_like(&mut env, &obj_class, expected_class_name);
+                if expected_class.is_err() {
+                    let _ = env.exception_clear();
+                }
+                if let Ok(expected_class) = expected_class {
                     if let Ok(same_class) = env.is_same_object(&obj_class, &expected_class) {
                         if same_class {
                             return SignatureMatch::Exact;
@@ -522,40 +623,83 @@ impl MinecraftClass {
     }
 
     pub fn get_field(&self, name: &str) -> &Field {
-        self.fields
-            .get(name)
+        self.find_field(name)
             .log_expect(format!("{} field not found", name).as_str())
     }
+
+    pub fn find_field(&self, name: &str) -> Option<&Field> {
+        self.fields.get(name)
+    }
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum MinecraftClassType {
-    Minecraft,
-    LocalPlayer,
-    Level,
-    Player,
-    Abilities,
-    Entity,
-    Vec3,
-    Window,
+/// Handle of a mapped class, keyed by its Mojang name
+///
+/// The classes used by the client are predefined as associated constants, any other module can
+/// declare its own handle with [`MinecraftClassType::new`]:
+/// ```ignore
+/// const SIGN: MinecraftClassType = MinecraftClassType::new("net/minecraft/world/level/block/entity/SignBlockEntity");
+/// ```
+/// Classes outside the mapping, like LWJGL or other mods, are declared with [`MinecraftClassType::unmapped`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MinecraftClassType {
+    name: &'static str,
+    mapped: bool,
 }
 
+#[allow(non_upper_case_globals, dead_code)]
 impl MinecraftClassType {
-    pub fn get_name(&self) -> &str {
-        match self {
-            MinecraftClassType::Minecraft => "net/minecraft/client/Minecraft",
-            MinecraftClassType::LocalPlayer => "net/minecraft/client/player/LocalPlayer",
-            MinecraftClassType::Level => "net/minecraft/client/multiplayer/ClientLevel",
-            MinecraftClassType::Player => "net/minecraft/world/entity/player/Player",
-            MinecraftClassType::Abilities => "net/minecraft/world/entity/player/Abilities",
-            MinecraftClassType::Entity => "net/minecraft/world/entity/Entity",
-            MinecraftClassType::Vec3 => "net/minecraft/world/phys/Vec3",
-            MinecraftClassType::Window => "com/mojang/blaze3d/platform/Window",
+    pub const Minecraft: Self = Self::new("net/minecraft/client/Minecraft");
+    pub const LocalPlayer: Self = Self::new("net/minecraft/client/player/LocalPlayer");
+    pub const Level: Self = Self::new("net/minecraft/client/multiplayer/ClientLevel");
+    pub const Player: Self = Self::new("net/minecraft/world/entity/player/Player");
+    pub const Abilities: Self = Self::new("net/minecraft/world/entity/player/Abilities");
+    pub const Entity: Self = Self::new("net/minecraft/world/entity/Entity");
+    pub const Vec3: Self = Self::new("net/minecraft/world/phys/Vec3");
+    pub const Window: Self = Self::new("com/mojang/blaze3d/platform/Window");
+    pub const Component: Self = Self::new("net/minecraft/network/chat/Component");
+    pub const ComponentSerialization: Self =
+        Self::new("net/minecraft/network/chat/ComponentSerialization");
+
+    /// The predefined handles, their hierarchy is read from the game at startup
+    pub const ALL: &'static [MinecraftClassType] = &[
+        MinecraftClassType::Minecraft,
+        MinecraftClassType::LocalPlayer,
+        MinecraftClassType::Level,
+        MinecraftClassType::Player,
+        MinecraftClassType::Abilities,
+        MinecraftClassType::Entity,
+        MinecraftClassType::Vec3,
+        MinecraftClassType::Window,
+        MinecraftClassType::Component,
+        MinecraftClassType::ComponentSerialization,
+    ];
+
+    /// Declares a handle for a class by Mojang internal name, e.g. `net/minecraft/world/phys/AABB`
+    pub const fn new(name: &'static str) -> Self {
+        Self { name, mapped: true }
+    }
+
+    /// Declares a handle for a class that is not in the mapping, by runtime internal name
+    ///
+    /// Its members are found through reflection and called by their runtime names,
+    /// the class is loaded with the game's context class loader.
+    pub const fn unmapped(name: &'static str) -> Self {
+        Self {
+            name,
+            mapped: false,
         }
     }
+
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn is_mapped(&self) -> bool {
+        self.mapped
+    }
 }
 
+/// Type of a field, covering the JVM field descriptor grammar
 #[allow(dead_code)]
 pub enum FieldType<'local> {
     Boolean,
@@ -567,10 +711,14 @@ pub enum FieldType<'local> {
     Float,
     Double,
     String,
+    /// A mapped class, its runtime name is read from the mapping
     Object(MinecraftClassType, &'local Mapping),
+    /// Any other class by runtime internal name, e.g. `java/util/List`
+    Class(&'local str),
+    Array(Box<FieldType<'local>>),
 }
 
-impl FieldType<'_> {
+impl<'local> FieldType<'local> {
     pub fn get_signature(&self) -> String {
         match self {
             FieldType::Boolean => String::from("Z"),
@@ -582,96 +730,593 @@ impl FieldType<'_> {
             FieldType::Float => String::from("F"),
             FieldType::Double => String::from("D"),
             FieldType::String => String::from("Ljava/lang/String;"),
+            FieldType::Object(minecraft_class_type, _) if !minecraft_class_type.is_mapped() => {
+                format!("L{};", minecraft_class_type.get_name())
+            }
             FieldType::Object(minecraft_class_type, mapping) => {
                 let class_name = &mapping.get_class(minecraft_class_type.get_name()).name;
                 format!("L{};", class_name)
             }
+            FieldType::Class(class_name) => format!("L{};", class_name),
+            FieldType::Array(element_type) => format!("[{}", element_type.get_signature()),
         }
     }
+
+    pub fn array(element_type: FieldType<'local>) -> FieldType<'local> {
+        FieldType::Array(Box::new(element_type))
+    }
+
+    /// Parses a runtime field descriptor, mapped classes are kept as [`FieldType::Class`]
+    ///
+    /// # Example
+    /// `[Ljava/util/List;` -> `FieldType::Array(FieldType::Class("java/util/List"))`
+    pub fn from_descriptor(descriptor: &'local str) -> Option<FieldType<'local>> {
+        let field_type = match descriptor {
+            "Z" => FieldType::Boolean,
+            "B" => FieldType::Byte,
+            "C" => FieldType::Char,
+            "S" => FieldType::Short,
+            "I" => FieldType::Int,
+            "J" => FieldType::Long,
+            "F" => FieldType::Float,
+            "D" => FieldType::Double,
+            "Ljava/lang/String;" => FieldType::String,
+            _ => match descriptor.strip_prefix('[') {
+                Some(element_type) => FieldType::array(Self::from_descriptor(element_type)?),
+                None => {
+                    let class_name = descriptor.strip_prefix('L')?.strip_suffix(';')?;
+                    if class_name.is_empty() || class_name.contains(';') {
+                        return None;
+                    }
+                    FieldType::Class(class_name)
+                }
+            },
+        };
+        Some(field_type)
+    }
 }
 
 impl Mapping {
-    pub fn new() -> Self {
-        let contents = include_str!("../../../mappings.json");
-        let mapping: Mapping = serde_json::from_str(contents).log_expect("Failed to parse mapping");
-        mapping
+    /// Loads the mapping matching the game version from the configured sources, see [`Mapping::load`],
+    /// and remaps it to the namespace used by the game
+    pub fn new(version: Option<&str>) -> Result<Self, MappingLoadError> {
+        let (mut mapping, _) = Self::load(version)?;
+        mapping.apply_overlays(version);
+        mapping.select_namespace(version)?;
+        mapping.capture_class_loader();
+        mapping.discover_hierarchy();
+        Ok(mapping)
+    }
+
+    pub fn get_version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn set_version(&mut self, version: Option<String>) {
+        self.version = version;
     }
 
     fn get_client(&self) -> &DarkClient {
         DarkClient::instance()
     }
 
-    fn get_env(&self) -> JNIEnv {
+    fn get_env(&self) -> JNIEnv<'_> {
         self.get_client()
             .get_env()
             .log_expect("Failed to get jni env")
     }
 
+    /// Returns the JNI environment of the current thread, failing with the pending Java exception if there is one
+    ///
+    /// JNI calls are undefined behaviour while an exception is pending, so every fallible entry point
+    /// starts here, and the exception is cleared to keep the thread usable.
+    fn try_get_env(&self) -> Result<JNIEnv<'_>, MappingError> {
+        let mut env = self.get_client().get_env().map_err(MappingError::Jni)?;
+        match self.take_exception(&mut env) {
+            Some(error) => Err(error),
+            None => Ok(env),
+        }
+    }
+
     pub fn get_class(&self, name: &str) -> &MinecraftClass {
-        self.classes
-            .get(name)
+        self.try_get_class(name)
             .log_expect(format!("{} java class not found", name).as_str())
     }
 
+    pub fn try_get_class(&self, name: &str) -> Result<&MinecraftClass, MappingError> {
+        self.class_entry(name)
+            .map(|(_, class)| class)
+            .ok_or_else(|| MappingError::MissingClass(name.to_string()))
+    }
+
+    /// Returns the overload of `method_name` matching `args`
+    ///
+    /// The method is looked up in the class first, then in its superclasses and interfaces.
+    /// `method_name` can select the overload with Java parameter types, e.g. `setPos(double,double,double)`,
+    /// or with a descriptor using Mojang class names, e.g. `setPos(Lnet/minecraft/world/phys/Vec3;)V`.
+    /// Otherwise the overload is chosen from the arguments, and a call matching several overloads is rejected.
+    pub fn resolve_method(
+        &self,
+        class_type: MinecraftClassType,
+        method_name: &str,
+        args: &[JValue],
+    ) -> Result<&Method, MappingError> {
+        let class = self.try_get_class(class_type.get_name())?;
+        self.select_method(class_type, class, method_name, args, |name| {
+            self.overloads(class_type, name)
+        })
+    }
+
+    /// Chooses the overload of `method_name` among the candidates returned by `overloads` for its name
+    fn select_method<'a>(
+        &self,
+        class_type: MinecraftClassType,
+        class: &MinecraftClass,
+        method_name: &str,
+        args: &[JValue],
+        overloads: impl FnOnce(&str) -> Vec<&'a Method>,
+    ) -> Result<&'a Method, MappingError> {
+        let selector = MethodSelector::parse(method_name);
+        let name = selector
+            .as_ref()
+            .map_or(method_name, |selector| selector.name);
+
+        let overloads = overloads(name);
+        let matching = match &selector {
+            Some(selector) => self.select_overloads(&overloads, selector),
+            None if overloads.len() == 1 => overloads.clone(),
+            None => class.matching_overloads(&overloads, args),
+        };
+
+        match matching.as_slice() {
+            [method] => Ok(method),
+            _ if overloads.is_empty() => Err(MappingError::MissingMethod {
+                class: class_type.get_name().to_string(),
+                method: name.to_string(),
+            }),
+            [] => Err(MappingError::SignatureMismatch {
+                class: class_type.get_name().to_string(),
+                member: name.to_string(),
+                expected: overloads
+                    .iter()
+                    .map(|method| self.describe_overload(class, name, method))
+                    .collect::<Vec<_>>()
+                    .join(" or "),
+                found: match selector {
+                    Some(_) => method_name.to_string(),
+                    None => describe_args(name, args),
+                },
+            }),
+            _ => Err(MappingError::AmbiguousMethod {
+                class: class_type.get_name().to_string(),
+                method: name.to_string(),
+                candidates: matching
+                    .iter()
+                    .map(|method| self.describe_overload(class, name, method))
+                    .collect(),
+            }),
+        }
+    }
+
+    /// Returns the overload of `method_name` with the given descriptor
+    ///
+    /// Unlike [`MinecraftClass::get_method_by_signature`], the descriptor can use Mojang class names,
+    /// e.g. `(Lnet/minecraft/world/phys/Vec3;)V`, or the runtime ones.
+    pub fn resolve_method_by_signature(
+        &self,
+        class_type: MinecraftClassType,
+        method_name: &str,
+        signature: &str,
+    ) -> Result<&Method, MappingError> {
+        self.resolve_method(class_type, &format!("{}{}", method_name, signature), &[])
+    }
+
+    /// Returns the overloads of a method in the class and its ancestors, overridden ones only once
+    ///
+    /// Constructors are not inherited, they are only looked up in the class itself.
+    fn overloads(&self, class_type: MinecraftClassType, name: &str) -> Vec<&Method> {
+        let classes = if name == CONSTRUCTOR {
+            self.class_entry(class_type.get_name())
+                .map(|(_, class)| class)
+                .into_iter()
+                .collect()
+        } else {
+            self.hierarchy(class_type.get_name())
+        };
+
+        let mut overloads: Vec<&Method> = Vec::new();
+        for class in classes {
+            for method in class.find_methods(name).into_iter().flatten() {
+                if !overloads
+                    .iter()
+                    .any(|overload| overload.signature == method.signature)
+                {
+                    overloads.push(method);
+                }
+            }
+        }
+        overloads
+    }
+
+    /// Returns the overloads whose parameters, and return type if given, match the selector
+    fn select_overloads<'a>(
+        &self,
+        overloads: &[&'a Method],
+        selector: &MethodSelector,
+    ) -> Vec<&'a Method> {
+        let remap = |name: &str| self.runtime_name(name);
+        let parameters = remap_descriptor(&selector.parameters, remap);
+        let return_type = selector
+            .return_type
+            .map(|return_type| remap_descriptor(return_type, remap));
+
+        overloads
+            .iter()
+            .filter(|method| {
+                let Some((method_parameters, method_return_type)) = method
+                    .signature
+                    .strip_prefix('(')
+                    .and_then(|signature| signature.split_once(')'))
+                else {
+                    return false;
+                };
+                method_parameters == parameters
+                    && return_type
+                        .as_ref()
+                        .is_none_or(|return_type| return_type == method_return_type)
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Formats an overload with Java parameter types and Mojang class names, e.g. `setPos(double,double,double)`
+    fn describe_overload(&self, class: &MinecraftClass, name: &str, method: &Method) -> String {
+        let parameters = class
+            .extract_parameter_types(&self.deobfuscate_descriptor(&method.signature))
+            .unwrap_or_default()
+            .iter()
+            .map(|parameter| descriptor_to_java_type(parameter))
+            .collect::<Vec<_>>();
+        format!("{}({})", name, parameters.join(","))
+    }
+
+    /// Returns a field of the class or of its superclasses and interfaces, like [`Mapping::resolve_method`]
+    pub fn resolve_field(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+    ) -> Result<&Field, MappingError> {
+        self.try_get_class(class_type.get_name())?;
+        self.hierarchy(class_type.get_name())
+            .into_iter()
+            .find_map(|class| class.find_field(field_name))
+            .ok_or_else(|| MappingError::MissingField {
+                class: class_type.get_name().to_string(),
+                field: field_name.to_string(),
+            })
+    }
+
     pub fn call_static_method(
         &self,
         class_type: MinecraftClassType,
         method_name: &str,
         args: &[JValue],
-    ) -> JValueOwned {
-        let mut env = self.get_env();
-
-        let class = self.get_class(class_type.get_name());
-        let jclass = env
-            .find_class(&class.name)
-            .log_expect(format!("{} class not found", class_type.get_name()).as_str());
-        let method = class.get_method_by_args(method_name, args);
-        env.call_static_method(jclass, &method.name, &method.signature, args)
+    ) -> JValueOwned<'_> {
+        self.try_call_static_method(class_type, method_name, args)
             .log_expect(
                 format!(
-                    "Error when calling static method {} in class {} with method signature {}",
-                    method.name, class.name, method.signature
+                    "Error when calling static method {}.{}",
+                    class_type, method_name
                 )
                 .as_str(),
             )
     }
 
+    pub fn try_call_static_method(
+        &self,
+        class_type: MinecraftClassType,
+        method_name: &str,
+        args: &[JValue],
+    ) -> Result<JValueOwned<'_>, MappingError> {
+        let mut env = self.try_get_env()?;
+
+        let class = self.class_ref(&mut env, class_type)?;
+        let method = self.resolve_method_in(class_type, &class, method_name, args)?;
+        let jclass = self
+            .cache
+            .class(&mut env, class_type, &class.name)
+            .map_err(|e| self.java_error(&mut env, e))?;
+        let cached = self
+            .cache
+            .static_method(
+                &mut env,
+                class_type,
+                &class.name,
+                method_name,
+                &method.name,
+                &method.signature,
+            )
+            .map_err(|e| self.java_error(&mut env, e))?;
+        Self::check_arg_count(class_type, method_name, cached.arg_count, args)?;
+
+        let args: Vec<jvalue> = args.iter().map(JValue::as_jni).collect();
+        // Safety: the method ID belongs to this class and the arguments match its signature
+        unsafe {
+            env.call_static_method_unchecked(
+                <&JClass>::from(jclass.as_obj()),
+                cached.id,
+                cached.return_type,
+                &args,
+            )
+        }
+        .map_err(|e| self.java_error(&mut env, e))
+    }
+
     pub fn call_method(
         &self,
         class_type: MinecraftClassType,
         instance: &JObject,
         method_name: &str,
         args: &[JValue],
-    ) -> JValueOwned {
-        let mut env = self.get_env();
+    ) -> JValueOwned<'_> {
+        self.try_call_method(class_type, instance, method_name, args)
+            .log_expect(
+                format!("Error when calling method {}.{}", class_type, method_name).as_str(),
+            )
+    }
+
+    pub fn try_call_method(
+        &self,
+        class_type: MinecraftClassType,
+        instance: &JObject,
+        method_name: &str,
+        args: &[JValue],
+    ) -> Result<JValueOwned<'_>, MappingError> {
+        Self::check_receiver(class_type, instance, method_name)?;
+        let mut env = self.try_get_env()?;
+
+        let class = self.class_ref(&mut env, class_type)?;
+        let method = self.resolve_method_in(class_type, &class, method_name, args)?;
+        let cached = self
+            .cache
+            .method(
+                &mut env,
+                class_type,
+                &class.name,
+                method_name,
+                &method.name,
+                &method.signature,
+            )
+            .map_err(|e| self.java_error(&mut env, e))?;
+        Self::check_arg_count(class_type, method_name, cached.arg_count, args)?;
+
+        let args: Vec<jvalue> = args.iter().map(JValue::as_jni).collect();
+        // Safety: the method ID belongs to the mapped class and the arguments match its signature
+        unsafe { env.call_method_unchecked(instance, cached.id, cached.return_type, &args) }
+            .map_err(|e| self.java_error(&mut env, e))
+    }
+
+    /// Creates an instance of a mapped class, the constructor is chosen like [`Mapping::resolve_method`]
+    pub fn new_object(&self, class_type: MinecraftClassType, args: &[JValue]) -> JObject<'_> {
+        self.try_new_object(class_type, args)
+            .log_expect(format!("Error when creating an instance of {}", class_type).as_str())
+    }
+
+    pub fn try_new_object(
+        &self,
+        class_type: MinecraftClassType,
+        args: &[JValue],
+    ) -> Result<JObject<'_>, MappingError> {
+        self.new_object_with(class_type, CONSTRUCTOR, args)
+    }
+
+    /// Creates an instance using the constructor with the given parameter types,
+    /// e.g. `(double,double,double)` or `(Lnet/minecraft/core/Vec3i;)V`
+    pub fn try_new_object_by_signature(
+        &self,
+        class_type: MinecraftClassType,
+        parameters: &str,
+        args: &[JValue],
+    ) -> Result<JObject<'_>, MappingError> {
+        self.new_object_with(class_type, &format!("{}{}", CONSTRUCTOR, parameters), args)
+    }
+
+    fn new_object_with(
+        &self,
+        class_type: MinecraftClassType,
+        constructor: &str,
+        args: &[JValue],
+    ) -> Result<JObject<'_>, MappingError> {
+        let mut env = self.try_get_env()?;
+
+        let class = self.class_ref(&mut env, class_type)?;
+        let method = self.resolve_method_in(class_type, &class, constructor, args)?;
+        let jclass = self
+            .cache
+            .class(&mut env, class_type, &class.name)
+            .map_err(|e| self.java_error(&mut env, e))?;
+        let cached = self
+            .cache
+            .method(
+                &mut env,
+                class_type,
+                &class.name,
+                constructor,
+                &method.name,
+                &method.signature,
+            )
+            .map_err(|e| self.java_error(&mut env, e))?;
+        Self::check_arg_count(class_type, constructor, cached.arg_count, args)?;
+
+        let args: Vec<jvalue> = args.iter().map(JValue::as_jni).collect();
+        // Safety: the constructor ID belongs to this class and the arguments match its signature
+        unsafe { env.new_object_unchecked(<&JClass>::from(jclass.as_obj()), cached.id, &args) }
+            .map_err(|e| self.java_error(&mut env, e))
+    }
+
+    fn check_receiver(
+        class_type: MinecraftClassType,
+        instance: &JObject,
+        member: &str,
+    ) -> Result<(), MappingError> {
+        if instance.is_null() {
+            Err(MappingError::NullReceiver {
+                class: class_type.get_name().to_string(),
+                member: member.to_string(),
+            })
+        } else {
+            Ok(())
+        }
+    }
 
-        let class = self.get_class(class_type.get_name());
-        let method = class.get_method_by_args(method_name, args);
-        env.call_method(instance, &method.name, &method.signature, args)
+    fn check_arg_count(
+        class_type: MinecraftClassType,
+        method_name: &str,
+        expected: usize,
+        args: &[JValue],
+    ) -> Result<(), MappingError> {
+        if expected == args.len() {
+            Ok(())
+        } else {
+            Err(MappingError::SignatureMismatch {
+                class: class_type.get_name().to_string(),
+                member: method_name.to_string(),
+                expected: format!("{} arguments", expected),
+                found: format!("{} arguments", args.len()),
+            })
+        }
+    }
+
+    /// Rejects a value whose kind doesn't match the field descriptor, JNI would store it anyway
+    fn check_value_type(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+        signature: &str,
+        value: &JValue,
+    ) -> Result<(), MappingError> {
+        let matches = matches!(
+            (signature.as_bytes().first(), value),
+            (Some(b'Z'), JValue::Bool(_))
+                | (Some(b'B'), JValue::Byte(_))
+                | (Some(b'C'), JValue::Char(_))
+                | (Some(b'S'), JValue::Short(_))
+                | (Some(b'I'), JValue::Int(_))
+                | (Some(b'J'), JValue::Long(_))
+                | (Some(b'F'), JValue::Float(_))
+                | (Some(b'D'), JValue::Double(_))
+                | (Some(b'L' | b'['), JValue::Object(_))
+        );
+
+        if matches {
+            Ok(())
+        } else {
+            Err(MappingError::SignatureMismatch {
+                class: class_type.get_name().to_string(),
+                member: field_name.to_string(),
+                expected: descriptor_to_java_type(&self.deobfuscate_descriptor(signature)),
+                found: value.type_name().to_string(),
+            })
+        }
+    }
+
+    pub fn get_static_field(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+        field_type: FieldType,
+    ) -> JValueOwned<'_> {
+        self.try_get_static_field(class_type, field_name, field_type)
             .log_expect(
                 format!(
-                    "Error when calling method {} in class {} with method signature {}",
-                    method.name, class.name, method.signature
+                    "Error when getting static field {}.{}",
+                    class_type, field_name
                 )
                 .as_str(),
             )
     }
 
-    pub fn get_static_field(
+    pub fn try_get_static_field(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+        field_type: FieldType,
+    ) -> Result<JValueOwned<'_>, MappingError> {
+        let mut env = self.try_get_env()?;
+
+        let class = self.class_ref(&mut env, class_type)?;
+        let field = self.resolve_field_in(class_type, &class, field_name)?;
+        let signature = self.check_field_signature(class_type, field_name, field, &field_type)?;
+        let jclass = self
+            .cache
+            .class(&mut env, class_type, &class.name)
+            .map_err(|e| self.java_error(&mut env, e))?;
+        let cached = self
+            .cache
+            .static_field(
+                &mut env,
+                class_type,
+                &class.name,
+                field_name,
+                &field.name,
+                &signature,
+            )
+            .map_err(|e| self.java_error(&mut env, e))?;
+        env.get_static_field_unchecked(
+            <&JClass>::from(jclass.as_obj()),
+            cached.id,
+            cached.java_type,
+        )
+        .map_err(|e| self.java_error(&mut env, e))
+    }
+
+    pub fn set_static_field(
         &self,
         class_type: MinecraftClassType,
         field_name: &str,
         field_type: FieldType,
-    ) -> JValueOwned {
-        let mut env = self.get_env();
+        value: JValue,
+    ) {
+        self.try_set_static_field(class_type, field_name, field_type, value)
+            .log_expect(
+                format!(
+                    "Error when setting static field {}.{}",
+                    class_type, field_name
+                )
+                .as_str(),
+            )
+    }
 
-        let class = self.get_class(class_type.get_name());
-        let jclass = env
-            .find_class(&class.name)
-            .log_expect(format!("{} class not found", class_type.get_name()).as_str());
-        let field = class.get_field(field_name);
-        env.get_static_field(jclass, &field.name, field_type.get_signature())
-            .log_expect(format!("Error when getting static field {}", field.name).as_str())
+    pub fn try_set_static_field(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+        field_type: FieldType,
+        value: JValue,
+    ) -> Result<(), MappingError> {
+        let mut env = self.try_get_env()?;
+
+        let class = self.class_ref(&mut env, class_type)?;
+        let field = self.resolve_field_in(class_type, &class, field_name)?;
+        let signature = self.check_field_signature(class_type, field_name, field, &field_type)?;
+        self.check_value_type(class_type, field_name, &signature, &value)?;
+        let jclass = self
+            .cache
+            .class(&mut env, class_type, &class.name)
+            .map_err(|e| self.java_error(&mut env, e))?;
+        let cached = self
+            .cache
+            .static_field(
+                &mut env,
+                class_type,
+                &class.name,
+                field_name,
+                &field.name,
+                &signature,
+            )
+            .map_err(|e| self.java_error(&mut env, e))?;
+        env.set_static_field(<&JClass>::from(jclass.as_obj()), cached.id, value)
+            .map_err(|e| self.java_error(&mut env, e))
     }
 
     pub fn get_field(
@@ -680,14 +1325,37 @@ impl Mapping {
         instance: &JObject,
         field_name: &str,
         field_type: FieldType,
-    ) -> JValueOwned {
-        let mut env = self.get_env();
-
-        let class = self.get_class(class_type.get_name());
-        let field = class.get_field(field_name);
+    ) -> JValueOwned<'_> {
+        self.try_get_field(class_type, instance, field_name, field_type)
+            .log_expect(format!("Error when getting field {}.{}", class_type, field_name).as_str())
+    }
 
-        env.get_field(instance, &field.name, field_type.get_signature())
-            .log_expect(format!("Error when getting field {}", field.name).as_str())
+    pub fn try_get_field(
+        &self,
+        class_type: MinecraftClassType,
+        instance: &JObject,
+        field_name: &str,
+        field_type: FieldType,
+    ) -> Result<JValueOwned<'_>, MappingError> {
+        Self::check_receiver(class_type, instance, field_name)?;
+        let mut env = self.try_get_env()?;
+
+        let class = self.class_ref(&mut env, class_type)?;
+        let field = self.resolve_field_in(class_type, &class, field_name)?;
+        let signature = self.check_field_signature(class_type, field_name, field, &field_type)?;
+        let cached = self
+            .cache
+            .field(
+                &mut env,
+                class_type,
+                &class.name,
+                field_name,
+                &field.name,
+                &signature,
+            )
+            .map_err(|e| self.java_error(&mut env, e))?;
+        env.get_field_unchecked(instance, cached.id, cached.return_type)
+            .map_err(|e| self.java_error(&mut env, e))
     }
 
     pub fn set_field(
@@ -698,38 +1366,128 @@ impl Mapping {
         field_type: FieldType,
         value: JValue,
     ) {
-        let mut env = self.get_env();
+        self.try_set_field(class_type, instance, field_name, field_type, value)
+            .log_expect(format!("Error when setting field {}.{}", class_type, field_name).as_str())
+    }
+
+    pub fn try_set_field(
+        &self,
+        class_type: MinecraftClassType,
+        instance: &JObject,
+        field_name: &str,
+        field_type: FieldType,
+        value: JValue,
+    ) -> Result<(), MappingError> {
+        Self::check_receiver(class_type, instance, field_name)?;
+        let mut env = self.try_get_env()?;
+
+        let class = self.class_ref(&mut env, class_type)?;
+        let field = self.resolve_field_in(class_type, &class, field_name)?;
+        let signature = self.check_field_signature(class_type, field_name, field, &field_type)?;
+        self.check_value_type(class_type, field_name, &signature, &value)?;
+        let cached = self
+            .cache
+            .field(
+                &mut env,
+                class_type,
+                &class.name,
+                field_name,
+                &field.name,
+                &signature,
+            )
+            .map_err(|e| self.java_error(&mut env, e))?;
+        env.set_field_unchecked(instance, cached.id, value)
+            .map_err(|e| self.java_error(&mut env, e))
+    }
+
+    /// Drops the cached classes and member IDs, needed before reloading the client or the mapping
+    pub fn clear_cache(&self) {
+        self.cache.clear();
+    }
+
+    /// Returns the JNI signature of a field, taken from the mapping when it records the field type
+    ///
+    /// The requested `field_type` must match the recorded descriptor, otherwise the access
+    /// would only fail inside the JVM with a `NoSuchFieldError`.
+    pub fn get_field_signature(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+        field_type: &FieldType,
+    ) -> Result<String, MappingError> {
+        let field = self.resolve_field(class_type, field_name)?;
+        self.check_field_signature(class_type, field_name, field, field_type)
+    }
+
+    fn check_field_signature(
+        &self,
+        class_type: MinecraftClassType,
+        field_name: &str,
+        field: &Field,
+        field_type: &FieldType,
+    ) -> Result<String, MappingError> {
+        let requested = field_type.get_signature();
+
+        match &field.signature {
+            Some(signature) if *signature != requested => Err(MappingError::SignatureMismatch {
+                class: class_type.get_name().to_string(),
+                member: field_name.to_string(),
+                expected: descriptor_to_java_type(&self.deobfuscate_descriptor(signature)),
+                found: descriptor_to_java_type(&self.deobfuscate_descriptor(&requested)),
+            }),
+            Some(signature) => Ok(signature.clone()),
+            None => Ok(requested),
+        }
+    }
 
-        let class = self.get_class(class_type.get_name());
-        let field = class.get_field(field_name);
-        env.set_field(instance, &field.name, field_type.get_signature(), value)
-            .log_expect(format!("Error when setting field {}", field.name).as_str());
+    /// Replaces the runtime class names of a descriptor with the mapped ones
+    fn deobfuscate_descriptor(&self, descriptor: &str) -> String {
+        self.reverse().deobfuscate_descriptor(descriptor)
     }
 
     pub fn new_global_ref(&self, obj: JObject) -> GlobalRef {
-        let env = self.get_env();
-        env.new_global_ref(obj).unwrap()
+        self.try_new_global_ref(obj)
+            .log_expect("Failed to create a global reference")
+    }
+
+    pub fn try_new_global_ref(&self, obj: JObject) -> Result<GlobalRef, MappingError> {
+        let mut env = self.try_get_env()?;
+        env.new_global_ref(obj)
+            .map_err(|e| self.java_error(&mut env, e))
     }
 
     pub fn get_string(&self, obj: JObject) -> String {
-        let env = self.get_env();
-        let jstring = JString::from(obj);
-        unsafe {
-            let value = env
-                .get_string_unchecked(jstring.as_ref())
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .to_string();
-            value
+        self.try_get_string(obj)
+            .log_expect("Failed to read a Java string")
+    }
+
+    /// Reads a `java.lang.String`, failing on `null`
+    pub fn try_get_string(&self, obj: JObject) -> Result<String, MappingError> {
+        if obj.is_null() {
+            return Err(MappingError::Jni(jni::errors::Error::NullPtr("get_string")));
         }
+
+        let mut env = self.try_get_env()?;
+        let jstring = JString::from(obj);
+        env.get_string(&jstring)
+            .map(String::from)
+            .map_err(|e| self.java_error(&mut env, e))
     }
 }
 
-impl Default for Mapping {
-    fn default() -> Self {
-        Self::new()
+/// Loads a class through the class loader of `class`, or with `find_class` for bootstrap classes
+fn load_class_like<'local>(
+    env: &mut JNIEnv<'local>,
+    class: &JClass,
+    name: &str,
+) -> jni::errors::Result<JClass<'local>> {
+    let class_loader = env
+        .call_method(class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+        .l()?;
+    if class_loader.is_null() {
+        return env.find_class(name);
     }
+    class_loader::load_class(env, &class_loader, name).map(JClass::from)
 }
 
 // Implement Display for better error messages
@@ -739,6 +1497,27 @@ impl std::fmt::Display for MinecraftClassType {
     }
 }
 
+/// Formats the kinds of the arguments of a call, e.g. `setPos(double,object)`
+fn describe_args(name: &str, args: &[JValue]) -> String {
+    let args = args
+        .iter()
+        .map(|arg| match arg {
+            JValue::Object(object) if object.is_null() => "null",
+            JValue::Object(_) => "object",
+            JValue::Bool(_) => "boolean",
+            JValue::Byte(_) => "byte",
+            JValue::Char(_) => "char",
+            JValue::Short(_) => "short",
+            JValue::Int(_) => "int",
+            JValue::Long(_) => "long",
+            JValue::Float(_) => "float",
+            JValue::Double(_) => "double",
+            JValue::Void => "void",
+        })
+        .collect::<Vec<_>>();
+    format!("{}({})", name, args.join(","))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -749,6 +1528,8 @@ mod tests {
             name: "TestClass".to_string(),
             methods: HashMap::new(),
             fields: HashMap::new(),
+            superclass: None,
+            interfaces: Vec::new(),
         };
 
         // Test basic types
@@ -770,12 +1551,153 @@ mod tests {
         assert_eq!(class.extract_parameter_types("([I)V").unwrap(), vec!["[I"]);
     }
 
+    #[test]
+    fn test_field_signature() {
+        let mapping: Mapping = serde_json::from_str(
+            r#"{
+                "classes": {
+                    "net/minecraft/world/entity/Entity": {
+                        "name": "bxe",
+                        "methods": {},
+                        "fields": {
+                            "fallDistance": {"name": "n", "signature": "D"},
+                            "position": {"name": "o", "signature": "Lfby;"},
+                            "legacy": {"name": "p"}
+                        }
+                    },
+                    "net/minecraft/world/phys/Vec3": {"name": "fby", "methods": {}, "fields": {}}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mapping
+                .get_field_signature(
+                    MinecraftClassType::Entity,
+                    "fallDistance",
+                    &FieldType::Double
+                )
+                .unwrap(),
+            "D"
+        );
+        assert_eq!(
+            mapping
+                .get_field_signature(
+                    MinecraftClassType::Entity,
+                    "position",
+                    &FieldType::Object(MinecraftClassType::Vec3, &mapping)
+                )
+                .unwrap(),
+            "Lfby;"
+        );
+        // Fields without a recorded type trust the caller
+        assert_eq!(
+            mapping
+                .get_field_signature(MinecraftClassType::Entity, "legacy", &FieldType::Int)
+                .unwrap(),
+            "I"
+        );
+
+        let error = mapping
+            .get_field_signature(MinecraftClassType::Entity, "position", &FieldType::Float)
+            .unwrap_err();
+        assert!(matches!(
+            &error,
+            MappingError::SignatureMismatch { expected, found, .. }
+                if expected == "net.minecraft.world.phys.Vec3" && found == "float"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "net/minecraft/world/entity/Entity.position expects net.minecraft.world.phys.Vec3, but it was accessed with float"
+        );
+
+        assert!(matches!(
+            mapping.get_field_signature(MinecraftClassType::Entity, "missing", &FieldType::Int),
+            Err(MappingError::MissingField { .. })
+        ));
+    }
+
+    #[test]
+    fn test_declared_class_type() {
+        const AABB: MinecraftClassType = MinecraftClassType::new("net/minecraft/world/phys/AABB");
+        let mapping: Mapping = serde_json::from_str(
+            r#"{
+                "classes": {
+                    "net/minecraft/world/phys/AABB": {
+                        "name": "fbx",
+                        "methods": {"getCenter": {"name": "f", "signature": "()Lfby;"}},
+                        "fields": {"minX": {"name": "a", "signature": "D"}}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            mapping.resolve_method(AABB, "getCenter", &[]).unwrap().name,
+            "f"
+        );
+        assert_eq!(mapping.resolve_field(AABB, "minX").unwrap().name, "a");
+        assert_eq!(
+            MinecraftClassType::new("net/minecraft/world/phys/Vec3"),
+            MinecraftClassType::Vec3
+        );
+        assert!(matches!(
+            mapping.resolve_method(MinecraftClassType::Vec3, "add", &[]),
+            Err(MappingError::MissingClass(_))
+        ));
+    }
+
+    #[test]
+    fn test_field_type_descriptors() {
+        for descriptor in [
+            "Z",
+            "[B",
+            "[[I",
+            "Ljava/lang/String;",
+            "Ljava/util/List;",
+            "[Lfby;",
+        ] {
+            assert_eq!(
+                FieldType::from_descriptor(descriptor)
+                    .unwrap()
+                    .get_signature(),
+                descriptor
+            );
+        }
+        assert!(matches!(
+            FieldType::from_descriptor("[Ljava/util/List;"),
+            Some(FieldType::Array(element)) if matches!(*element, FieldType::Class("java/util/List"))
+        ));
+        for invalid in ["", "V", "[", "L;", "Ljava/util/List", "II", "La;b;"] {
+            assert!(FieldType::from_descriptor(invalid).is_none(), "{}", invalid);
+        }
+
+        let mapping: Mapping = serde_json::from_str(r#"{"classes": {}}"#).unwrap();
+        assert!(mapping
+            .check_value_type(
+                MinecraftClassType::Entity,
+                "ids",
+                "[I",
+                &JValue::Object(&JObject::null())
+            )
+            .is_ok());
+        assert!(matches!(
+            mapping.check_value_type(MinecraftClassType::Entity, "flying", "Z", &JValue::Int(1)),
+            Err(MappingError::SignatureMismatch { expected, found, .. })
+                if expected == "boolean" && found == "int"
+        ));
+    }
+
     #[test]
     fn test_type_compatibility() {
         let class = MinecraftClass {
             name: "TestClass".to_string(),
             methods: HashMap::new(),
             fields: HashMap::new(),
+            superclass: None,
+            interfaces: Vec::new(),
         };
 
         // Test exact matches
@@ -796,4 +1718,157 @@ mod tests {
             SignatureMatch::Incompatible
         );
     }
+
+    #[test]
+    fn test_overload_selection() {
+        let mapping: Mapping = serde_json::from_str(
+            r#"{
+                "classes": {
+                    "net/minecraft/world/entity/Entity": {
+                        "name": "bxe",
+                        "methods": {
+                            "setPos": [
+                                {"name": "a", "signature": "(DDD)V"},
+                                {"name": "b", "signature": "(Lfby;)V"}
+                            ],
+                            "teleportTo": [
+                                {"name": "c", "signature": "(Lfby;)Z"},
+                                {"name": "d", "signature": "(Ljava/lang/String;)Z"}
+                            ]
+                        },
+                        "fields": {}
+                    },
+                    "net/minecraft/world/phys/Vec3": {"name": "fby", "methods": {}, "fields": {}}
+                }
+            }"#,
+        )
+        .unwrap();
+        let resolve = |method_name: &str, args: &[JValue]| {
+            mapping
+                .resolve_method(MinecraftClassType::Entity, method_name, args)
+                .map(|method| method.name.as_str())
+        };
+        let position = [
+            JValue::Double(0.0),
+            JValue::Double(64.0),
+            JValue::Double(0.0),
+        ];
+        let null = JObject::null();
+
+        assert_eq!(resolve("setPos", &position).unwrap(), "a");
+        assert_eq!(resolve("setPos(double,double,double)", &[]).unwrap(), "a");
+        assert_eq!(
+            resolve("setPos(net.minecraft.world.phys.Vec3)", &[]).unwrap(),
+            "b"
+        );
+        assert_eq!(
+            resolve("setPos(Lnet/minecraft/world/phys/Vec3;)V", &[]).unwrap(),
+            "b"
+        );
+        assert_eq!(resolve("teleportTo(java.lang.String)", &[]).unwrap(), "d");
+
+        assert_eq!(
+            mapping
+                .resolve_method_by_signature(
+                    MinecraftClassType::Entity,
+                    "setPos",
+                    "(Lnet/minecraft/world/phys/Vec3;)V"
+                )
+                .unwrap()
+                .name,
+            "b"
+        );
+        assert_eq!(
+            mapping
+                .resolve_method_by_signature(MinecraftClassType::Entity, "setPos", "(Lfby;)V")
+                .unwrap()
+                .name,
+            "b"
+        );
+
+        // A null argument matches both object overloads
+        let error = resolve("teleportTo", &[JValue::Object(&null)]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "net/minecraft/world/entity/Entity.teleportTo is ambiguous, it matches \
+             teleportTo(net.minecraft.world.phys.Vec3), teleportTo(java.lang.String)"
+        );
+
+        let error = resolve("setPos(int)", &[]).unwrap_err();
+        assert!(matches!(
+            &error,
+            MappingError::SignatureMismatch { expected, .. }
+                if expected == "setPos(double,double,double) or setPos(net.minecraft.world.phys.Vec3)"
+        ));
+        assert!(matches!(
+            resolve("setPos(Lnet/minecraft/world/phys/Vec3;)Z", &[]),
+            Err(MappingError::SignatureMismatch { .. })
+        ));
+        assert!(matches!(
+            resolve("setPos", &[JValue::Int(0)]),
+            Err(MappingError::SignatureMismatch { found, .. }) if found == "setPos(int)"
+        ));
+        assert!(matches!(
+            resolve("move(double)", &[]),
+            Err(MappingError::MissingMethod { method, .. }) if method == "move"
+        ));
+    }
+
+    #[test]
+    fn test_constructor_resolution() {
+        let mapping: Mapping = serde_json::from_str(
+            r#"{
+                "classes": {
+                    "net/minecraft/world/phys/Vec3": {
+                        "name": "fby",
+                        "methods": {
+                            "<init>": [
+                                {"name": "<init>", "signature": "(DDD)V"},
+                                {"name": "<init>", "signature": "(Lorg/joml/Vector3fc;)V"}
+                            ]
+                        },
+                        "fields": {}
+                    },
+                    "net/minecraft/world/entity/Entity": {
+                        "name": "bxe",
+                        "methods": {"<init>": {"name": "<init>", "signature": "(Lbxj;Lcpv;)V"}},
+                        "fields": {}
+                    },
+                    "net/minecraft/client/player/LocalPlayer": {
+                        "name": "gkv",
+                        "methods": {},
+                        "fields": {},
+                        "superclass": "net/minecraft/world/entity/Entity"
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let position = [
+            JValue::Double(0.0),
+            JValue::Double(64.0),
+            JValue::Double(0.0),
+        ];
+        assert_eq!(
+            mapping
+                .resolve_method(MinecraftClassType::Vec3, CONSTRUCTOR, &position)
+                .unwrap()
+                .signature,
+            "(DDD)V"
+        );
+        assert_eq!(
+            mapping
+                .resolve_method(MinecraftClassType::Vec3, "<init>(org.joml.Vector3fc)", &[])
+                .unwrap()
+                .signature,
+            "(Lorg/joml/Vector3fc;)V"
+        );
+
+        // Constructors are not inherited
+        assert!(matches!(
+            mapping.resolve_method(MinecraftClassType::LocalPlayer, CONSTRUCTOR, &[]),
+            Err(MappingError::MissingMethod { .. })
+        ));
+    }
 }
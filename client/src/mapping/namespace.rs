@@ -0,0 +1,53 @@
+use crate::client::DarkClient;
+use crate::mapping::class_loader::find_game_class;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Intermediary name of `net/minecraft/client/Minecraft`, only loadable on Fabric
+const INTERMEDIARY_PROBE: &str = "net/minecraft/class_310";
+
+/// Naming scheme of the classes and members loaded by the running game
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Namespace {
+    /// Mojang obfuscated names, used by vanilla
+    #[default]
+    Official,
+    /// Fabric intermediary names (`net/minecraft/class_310`)
+    Intermediary,
+}
+
+impl Namespace {
+    /// Name of the namespace in Tiny mapping files
+    pub fn tiny_name(&self) -> &'static str {
+        match self {
+            Namespace::Official => "official",
+            Namespace::Intermediary => "intermediary",
+        }
+    }
+
+    /// Detects the namespace by checking whether the intermediary classes exist
+    pub fn detect() -> Namespace {
+        let Ok(mut env) = DarkClient::instance().get_env() else {
+            return Namespace::Official;
+        };
+
+        // The game classes are only visible to the class loader of the game
+        let found = matches!(find_game_class(&mut env, INTERMEDIARY_PROBE), Ok(Some(_)));
+        if env.exception_check().unwrap_or(false) {
+            let _ = env.exception_clear();
+        }
+
+        if found {
+            Namespace::Intermediary
+        } else {
+            Namespace::Official
+        }
+    }
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tiny_name())
+    }
+}
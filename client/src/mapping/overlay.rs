@@ -0,0 +1,357 @@
+use crate::mapping::descriptor::{remap_descriptor, DescriptorNames};
+use crate::mapping::{deserialize_methods, Field, Mapping, Method, MinecraftClass};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Small mapping file fixing or extending the base mapping, in the same format
+///
+/// Classes already in the mapping can leave out `name`. A method overload replaces the one with the
+/// same descriptor, a field replaces the one with the same name, anything else is added.
+#[derive(Debug, Deserialize)]
+pub struct Overlay {
+    /// Game version the overlay is for, it applies to any version if missing
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    descriptors: DescriptorNames,
+    classes: HashMap<String, OverlayClass>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OverlayClass {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_methods")]
+    methods: HashMap<String, Vec<Method>>,
+    #[serde(default)]
+    fields: HashMap<String, Field>,
+    #[serde(default)]
+    superclass: Option<String>,
+    #[serde(default)]
+    interfaces: Option<Vec<String>>,
+}
+
+/// Entry of the mapping changed by an overlay, logged when the overlay is applied
+#[derive(Debug, PartialEq, Eq)]
+pub enum OverlayChange {
+    AddedClass(String),
+    RenamedClass {
+        class: String,
+        from: String,
+        to: String,
+    },
+    AddedMethod {
+        class: String,
+        method: String,
+        signature: String,
+    },
+    RenamedMethod {
+        class: String,
+        method: String,
+        signature: String,
+        from: String,
+        to: String,
+    },
+    AddedField {
+        class: String,
+        field: String,
+    },
+    ReplacedField {
+        class: String,
+        field: String,
+        from: String,
+        to: String,
+    },
+    Hierarchy(String),
+}
+
+impl fmt::Display for OverlayChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverlayChange::AddedClass(class) => write!(f, "added class {}", class),
+            OverlayChange::RenamedClass { class, from, to } => {
+                write!(f, "class {}: {} -> {}", class, from, to)
+            }
+            OverlayChange::AddedMethod {
+                class,
+                method,
+                signature,
+            } => write!(f, "added method {}.{}{}", class, method, signature),
+            OverlayChange::RenamedMethod {
+                class,
+                method,
+                signature,
+                from,
+                to,
+            } => write!(
+                f,
+                "method {}.{}{}: {} -> {}",
+                class, method, signature, from, to
+            ),
+            OverlayChange::AddedField { class, field } => {
+                write!(f, "added field {}.{}", class, field)
+            }
+            OverlayChange::ReplacedField {
+                class,
+                field,
+                from,
+                to,
+            } => write!(f, "field {}.{}: {} -> {}", class, field, from, to),
+            OverlayChange::Hierarchy(class) => write!(f, "hierarchy of {}", class),
+        }
+    }
+}
+
+/// Formats a field as its runtime name and type, e.g. `ac D`
+fn describe_field(field: &Field) -> String {
+    match &field.signature {
+        Some(signature) => format!("{} {}", field.name, signature),
+        None => field.name.clone(),
+    }
+}
+
+impl Mapping {
+    /// Merges an overlay into the mapping and returns every entry it added or replaced
+    ///
+    /// Fails without changing the mapping if the overlay adds a class without a runtime name.
+    pub fn apply_overlay(&mut self, overlay: Overlay) -> Result<Vec<OverlayChange>, String> {
+        if let Some((name, _)) = overlay
+            .classes
+            .iter()
+            .find(|(name, class)| class.name.is_none() && !self.contains_class(name))
+        {
+            return Err(format!(
+                "class {} is not in the mapping and has no name",
+                name
+            ));
+        }
+
+        let mut changes = Vec::new();
+
+        // Classes first, the member descriptors may refer to any of them
+        let mut renamed: HashMap<String, String> = HashMap::new();
+        for (name, overlay_class) in &overlay.classes {
+            let Some(runtime_name) = &overlay_class.name else {
+                continue;
+            };
+            match self.class_mut(name) {
+                Some(class) if class.name != *runtime_name => {
+                    changes.push(OverlayChange::RenamedClass {
+                        class: name.clone(),
+                        from: class.name.clone(),
+                        to: runtime_name.clone(),
+                    });
+                    renamed.insert(class.name.clone(), runtime_name.clone());
+                    class.name = runtime_name.clone();
+                }
+                Some(_) => {}
+                None => {
+                    changes.push(OverlayChange::AddedClass(name.clone()));
+                    self.classes.insert(
+                        name.clone(),
+                        MinecraftClass {
+                            name: runtime_name.clone(),
+                            methods: HashMap::new(),
+                            fields: HashMap::new(),
+                            superclass: None,
+                            interfaces: Vec::new(),
+                        },
+                    );
+                }
+            }
+        }
+        if !renamed.is_empty() {
+            self.remap_descriptors(|name| renamed.get(name));
+        }
+
+        let runtime_names: HashMap<String, String> = self
+            .class_names()
+            .map(|(name, runtime_name)| (name.to_string(), runtime_name.to_string()))
+            .collect();
+        let resolve = |descriptor: String| match overlay.descriptors {
+            DescriptorNames::Runtime => descriptor,
+            DescriptorNames::Mojang => {
+                remap_descriptor(&descriptor, |name| runtime_names.get(name))
+            }
+        };
+
+        for (name, overlay_class) in overlay.classes {
+            let class = self.class_mut(&name).unwrap();
+
+            for (method_name, overloads) in overlay_class.methods {
+                let methods = class.methods.entry(method_name.clone()).or_default();
+                for mut method in overloads {
+                    method.signature = resolve(method.signature);
+                    match methods
+                        .iter_mut()
+                        .find(|existing| existing.signature == method.signature)
+                    {
+                        Some(existing) if existing.name != method.name => {
+                            changes.push(OverlayChange::RenamedMethod {
+                                class: name.clone(),
+                                method: method_name.clone(),
+                                signature: method.signature.clone(),
+                                from: existing.name.clone(),
+                                to: method.name.clone(),
+                            });
+                            existing.name = method.name;
+                        }
+                        Some(_) => {}
+                        None => {
+                            changes.push(OverlayChange::AddedMethod {
+                                class: name.clone(),
+                                method: method_name.clone(),
+                                signature: method.signature.clone(),
+                            });
+                            methods.push(method);
+                        }
+                    }
+                }
+            }
+
+            for (field_name, mut field) in overlay_class.fields {
+                field.signature = field.signature.map(&resolve);
+                match class.fields.get_mut(&field_name) {
+                    Some(existing) => {
+                        // A field without a type in the overlay only renames the mapped one
+                        field.signature = field.signature.or_else(|| existing.signature.clone());
+                        let (from, to) = (describe_field(existing), describe_field(&field));
+                        if from != to {
+                            changes.push(OverlayChange::ReplacedField {
+                                class: name.clone(),
+                                field: field_name,
+                                from,
+                                to,
+                            });
+                        }
+                        *existing = field;
+                    }
+                    None => {
+                        changes.push(OverlayChange::AddedField {
+                            class: name.clone(),
+                            field: field_name.clone(),
+                        });
+                        class.fields.insert(field_name, field);
+                    }
+                }
+            }
+
+            if overlay_class.superclass.is_some() || overlay_class.interfaces.is_some() {
+                if let Some(superclass) = overlay_class.superclass {
+                    class.superclass = Some(superclass);
+                }
+                if let Some(interfaces) = overlay_class.interfaces {
+                    class.interfaces = interfaces;
+                }
+                changes.push(OverlayChange::Hierarchy(name.clone()));
+            }
+        }
+
+        self.reverse = OnceLock::new();
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::MinecraftClassType;
+
+    fn mapping() -> Mapping {
+        serde_json::from_str(
+            r#"{
+                "classes": {
+                    "net/minecraft/world/entity/Entity": {
+                        "name": "bxe",
+                        "methods": {
+                            "position": {"name": "dt", "signature": "()Lfby;"},
+                            "setPos": [
+                                {"name": "a", "signature": "(DDD)V"},
+                                {"name": "a", "signature": "(Lfby;)V"}
+                            ]
+                        },
+                        "fields": {"fallDistance": {"name": "ac", "signature": "D"}}
+                    },
+                    "net/minecraft/world/phys/Vec3": {"name": "fby", "methods": {}, "fields": {}}
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply_overlay() {
+        let mut mapping = mapping();
+        let overlay: Overlay = serde_json::from_str(
+            r#"{
+                "descriptors": "mojang",
+                "classes": {
+                    "net/minecraft/world/phys/Vec3": {"name": "fbz"},
+                    "net/minecraft/world/entity/Entity": {
+                        "methods": {
+                            "setPos": {"name": "b", "signature": "(Lnet/minecraft/world/phys/Vec3;)V"},
+                            "getEyePosition": {"name": "dv", "signature": "()Lnet/minecraft/world/phys/Vec3;"}
+                        },
+                        "fields": {"fallDistance": {"name": "ad"}}
+                    },
+                    "net/minecraft/world/phys/AABB": {"name": "fbx", "fields": {"minX": {"name": "a", "signature": "D"}}}
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let mut changes: Vec<String> = mapping
+            .apply_overlay(overlay)
+            .unwrap()
+            .iter()
+            .map(OverlayChange::to_string)
+            .collect();
+        changes.sort();
+        assert_eq!(
+            changes,
+            vec![
+                "added class net/minecraft/world/phys/AABB",
+                "added field net/minecraft/world/phys/AABB.minX",
+                "added method net/minecraft/world/entity/Entity.getEyePosition()Lfbz;",
+                "class net/minecraft/world/phys/Vec3: fby -> fbz",
+                "field net/minecraft/world/entity/Entity.fallDistance: ac D -> ad D",
+                "method net/minecraft/world/entity/Entity.setPos(Lfbz;)V: a -> b",
+            ]
+        );
+
+        // Descriptors of the base mapping follow the renamed class
+        let position = mapping
+            .resolve_method(MinecraftClassType::Entity, "position", &[])
+            .unwrap();
+        assert_eq!(position.signature, "()Lfbz;");
+        let set_pos = mapping
+            .resolve_method_by_signature(
+                MinecraftClassType::Entity,
+                "setPos",
+                "(Lnet/minecraft/world/phys/Vec3;)V",
+            )
+            .unwrap();
+        assert_eq!(set_pos.name, "b");
+        let fall_distance = mapping
+            .resolve_field(MinecraftClassType::Entity, "fallDistance")
+            .unwrap();
+        assert_eq!(fall_distance.name, "ad");
+        assert_eq!(fall_distance.signature.as_deref(), Some("D"));
+    }
+
+    #[test]
+    fn test_overlay_new_class_needs_name() {
+        let mut mapping = mapping();
+        let overlay: Overlay = serde_json::from_str(
+            r#"{"classes": {"net/minecraft/world/phys/AABB": {"fields": {"minX": {"name": "a"}}}}}"#,
+        )
+        .unwrap();
+
+        assert!(mapping.apply_overlay(overlay).is_err());
+        assert!(mapping
+            .try_get_class("net/minecraft/world/phys/AABB")
+            .is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! Writer of the packed mapping format, also compiled into `build.rs` to pack the embedded mapping
+//!
+//! Integers are LEB128 varints and strings are stored once in a table, referenced by index:
+//! ```text
+//! magic, format version (u8), game version (u8 flag + string), namespace (string)
+//! string table: count, then length + UTF-8 bytes of each string
+//! classes: count, then for each class its Mojang name, runtime name, body length and body
+//! body: superclass (index + 1, 0 if none), interfaces, methods by Mojang name with their
+//!       overloads (runtime name, descriptor), fields (Mojang name, runtime name, descriptor + 1)
+//! ```
+//! Descriptors are always stored with runtime names. The body length lets a reader index the
+//! classes without decoding their members.
+
+use crate::mapping::jni_descriptor::remap_descriptor;
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const MAGIC: &[u8; 4] = b"DCPM";
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Strings of the mapping, deduplicated in first use order
+#[derive(Default)]
+struct StringTable {
+    ids: HashMap<String, usize>,
+    strings: Vec<String>,
+}
+
+impl StringTable {
+    fn id(&mut self, string: &str) -> usize {
+        if let Some(id) = self.ids.get(string) {
+            return *id;
+        }
+        let id = self.strings.len();
+        self.ids.insert(string.to_string(), id);
+        self.strings.push(string.to_string());
+        id
+    }
+}
+
+/// Packs a mapping in the JSON layout, e.g. the contents of `mappings.json`
+///
+/// Descriptors with Mojang names (`"descriptors": "mojang"`) are rewritten with the runtime names.
+pub fn encode(mapping: &Value) -> Result<Vec<u8>, String> {
+    let classes = mapping
+        .get("classes")
+        .and_then(Value::as_object)
+        .ok_or("missing classes")?;
+
+    let mut runtime_names: HashMap<&str, &str> = HashMap::new();
+    if mapping.get("descriptors").and_then(Value::as_str) == Some("mojang") {
+        for (name, class) in classes {
+            if let Some(runtime_name) = class.get("name").and_then(Value::as_str) {
+                runtime_names.insert(name, runtime_name);
+            }
+        }
+    }
+
+    let mut names: Vec<&String> = classes.keys().collect();
+    names.sort();
+
+    let mut strings = StringTable::default();
+    let mut entries = Vec::new();
+    for name in names {
+        let class = &classes[name];
+        let runtime_name = class
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("class {} has no name", name))?;
+        let body = encode_class(name, class, &mut strings, &runtime_names)?;
+        entries.push((strings.id(name), strings.id(runtime_name), body));
+    }
+
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    match mapping.get("version").and_then(Value::as_str) {
+        Some(version) => {
+            out.push(1);
+            write_str(&mut out, version);
+        }
+        None => out.push(0),
+    }
+    write_str(
+        &mut out,
+        mapping
+            .get("namespace")
+            .and_then(Value::as_str)
+            .unwrap_or("official"),
+    );
+
+    write_varint(&mut out, strings.strings.len());
+    for string in &strings.strings {
+        write_str(&mut out, string);
+    }
+
+    write_varint(&mut out, entries.len());
+    for (name, runtime_name, body) in entries {
+        write_varint(&mut out, name);
+        write_varint(&mut out, runtime_name);
+        write_varint(&mut out, body.len());
+        out.extend_from_slice(&body);
+    }
+
+    Ok(out)
+}
+
+fn encode_class(
+    name: &str,
+    class: &Value,
+    strings: &mut StringTable,
+    runtime_names: &HashMap<&str, &str>,
+) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    let descriptor = |strings: &mut StringTable, descriptor: &str| {
+        if runtime_names.is_empty() {
+            strings.id(descriptor)
+        } else {
+            strings.id(&remap_descriptor(descriptor, |name| {
+                runtime_names.get(name)
+            }))
+        }
+    };
+
+    let superclass = class.get("superclass").and_then(Value::as_str);
+    write_varint(
+        &mut body,
+        superclass.map_or(0, |superclass| strings.id(superclass) + 1),
+    );
+    let interfaces: Vec<&str> = class
+        .get("interfaces")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    write_varint(&mut body, interfaces.len());
+    for interface in interfaces {
+        write_varint(&mut body, strings.id(interface));
+    }
+
+    let methods = class.get("methods").and_then(Value::as_object);
+    write_varint(&mut body, methods.map_or(0, |methods| methods.len()));
+    for (method_name, overloads) in methods.into_iter().flatten() {
+        // A method without overloads is a single object
+        let overloads = match overloads {
+            Value::Array(overloads) => overloads.iter().collect(),
+            overload => vec![overload],
+        };
+        write_varint(&mut body, strings.id(method_name));
+        write_varint(&mut body, overloads.len());
+        for overload in overloads {
+            let (Some(runtime_name), Some(signature)) = (
+                overload.get("name").and_then(Value::as_str),
+                overload.get("signature").and_then(Value::as_str),
+            ) else {
+                return Err(format!(
+                    "method {}.{} has no name or signature",
+                    name, method_name
+                ));
+            };
+            write_varint(&mut body, strings.id(runtime_name));
+            write_varint(&mut body, descriptor(strings, signature));
+        }
+    }
+
+    let fields = class.get("fields").and_then(Value::as_object);
+    write_varint(&mut body, fields.map_or(0, |fields| fields.len()));
+    for (field_name, field) in fields.into_iter().flatten() {
+        let runtime_name = field
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("field {}.{} has no name", name, field_name))?;
+        write_varint(&mut body, strings.id(field_name));
+        write_varint(&mut body, strings.id(runtime_name));
+        let signature = field.get("signature").and_then(Value::as_str);
+        write_varint(
+            &mut body,
+            signature.map_or(0, |signature| descriptor(strings, signature) + 1),
+        );
+    }
+
+    Ok(body)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_str(out: &mut Vec<u8>, string: &str) {
+    write_varint(out, string.len());
+    out.extend_from_slice(string.as_bytes());
+}
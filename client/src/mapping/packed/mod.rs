@@ -0,0 +1,484 @@
+//! Compact binary mapping format, indexed at load time and decoded one class at a time
+//!
+//! Only the class names are read when the mapping is loaded, the members of a class are decoded
+//! the first time it is looked up. See [`format`] for the layout.
+
+use crate::mapping::cache::ResolutionCache;
+use crate::mapping::descriptor::DescriptorNames;
+use crate::mapping::namespace::Namespace;
+use crate::mapping::{Field, Mapping, Method, MinecraftClass};
+use log::error;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+pub mod format;
+
+/// Error raised while reading a packed mapping, `offset` is the position of the invalid data
+#[derive(Debug, PartialEq, Eq)]
+pub struct PackedError {
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for PackedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "offset {}: {}", self.offset, self.message)
+    }
+}
+
+impl std::error::Error for PackedError {}
+
+/// Returns true if the data starts like a packed mapping
+pub fn is_packed(data: &[u8]) -> bool {
+    data.starts_with(format::MAGIC)
+}
+
+/// Classes of a packed mapping, keyed by Mojang name
+pub struct PackedClasses {
+    data: Cow<'static, [u8]>,
+    /// Byte range of every string of the table
+    strings: Vec<Range<usize>>,
+    classes: HashMap<String, PackedClass>,
+}
+
+struct PackedClass {
+    name: String,
+    body: Range<usize>,
+    /// `None` if the body is invalid, the error is logged once
+    decoded: OnceLock<Option<MinecraftClass>>,
+}
+
+impl fmt::Debug for PackedClasses {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PackedClasses")
+            .field("bytes", &self.data.len())
+            .field("classes", &self.classes.len())
+            .field("decoded", &self.decoded_count())
+            .finish()
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, message: impl Into<String>) -> PackedError {
+        PackedError {
+            offset: self.position,
+            message: message.into(),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, PackedError> {
+        let byte = *self
+            .data
+            .get(self.position)
+            .ok_or_else(|| self.error("unexpected end of data"))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<usize, PackedError> {
+        let mut value = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(self.error("varint too long"))
+    }
+
+    /// Skips `len` bytes and returns their range
+    fn range(&mut self, len: usize) -> Result<Range<usize>, PackedError> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| self.error("unexpected end of data"))?;
+        let range = self.position..end;
+        self.position = end;
+        Ok(range)
+    }
+
+    fn str(&mut self) -> Result<&'a str, PackedError> {
+        let len = self.varint()?;
+        let range = self.range(len)?;
+        std::str::from_utf8(&self.data[range]).map_err(|_| self.error("invalid UTF-8"))
+    }
+}
+
+impl PackedClasses {
+    fn decoded_count(&self) -> usize {
+        self.classes
+            .values()
+            .filter(|class| class.decoded.get().is_some())
+            .count()
+    }
+
+    fn string(&self, reader: &Reader, id: usize) -> Result<&str, PackedError> {
+        let range = self
+            .strings
+            .get(id)
+            .ok_or_else(|| reader.error(format!("invalid string {}", id)))?;
+        std::str::from_utf8(&self.data[range.clone()]).map_err(|_| reader.error("invalid UTF-8"))
+    }
+
+    fn decode(&self, class: &PackedClass) -> Result<MinecraftClass, PackedError> {
+        let data = &self.data[..class.body.end];
+        let mut reader = Reader {
+            data,
+            position: class.body.start,
+        };
+        let string = |reader: &mut Reader| {
+            let id = reader.varint()?;
+            self.string(reader, id).map(String::from)
+        };
+
+        let superclass = match reader.varint()? {
+            0 => None,
+            id => Some(self.string(&reader, id - 1)?.to_string()),
+        };
+        let mut interfaces = Vec::new();
+        for _ in 0..reader.varint()? {
+            interfaces.push(string(&mut reader)?);
+        }
+
+        let mut methods = HashMap::new();
+        for _ in 0..reader.varint()? {
+            let method_name = string(&mut reader)?;
+            let mut overloads = Vec::new();
+            for _ in 0..reader.varint()? {
+                overloads.push(Method {
+                    name: string(&mut reader)?,
+                    signature: string(&mut reader)?,
+                });
+            }
+            methods.insert(method_name, overloads);
+        }
+
+        let mut fields = HashMap::new();
+        for _ in 0..reader.varint()? {
+            let field_name = string(&mut reader)?;
+            let name = string(&mut reader)?;
+            let signature = match reader.varint()? {
+                0 => None,
+                id => Some(self.string(&reader, id - 1)?.to_string()),
+            };
+            fields.insert(field_name, Field { name, signature });
+        }
+
+        if reader.position != class.body.end {
+            return Err(reader.error("class body has trailing data"));
+        }
+
+        Ok(MinecraftClass {
+            name: class.name.clone(),
+            methods,
+            fields,
+            superclass,
+            interfaces,
+        })
+    }
+
+    /// Returns the decoded class, decoding it on first use
+    fn get(&self, name: &str) -> Option<(&str, &MinecraftClass)> {
+        let (key, class) = self.classes.get_key_value(name)?;
+        let decoded = class.decoded.get_or_init(|| {
+            self.decode(class)
+                .inspect_err(|e| error!("Invalid packed class {}: {}", name, e))
+                .ok()
+        });
+        decoded.as_ref().map(|decoded| (key.as_str(), decoded))
+    }
+
+    /// Removes a class, decoding it if it wasn't yet
+    fn take(&mut self, name: &str) -> Option<MinecraftClass> {
+        let mut class = self.classes.remove(name)?;
+        match class.decoded.take() {
+            Some(decoded) => decoded,
+            None => self
+                .decode(&class)
+                .inspect_err(|e| error!("Invalid packed class {}: {}", name, e))
+                .ok(),
+        }
+    }
+}
+
+impl Mapping {
+    /// Reads a packed mapping, only the class names are decoded until a class is looked up
+    pub fn from_packed(data: impl Into<Cow<'static, [u8]>>) -> Result<Mapping, PackedError> {
+        let data = data.into();
+        let mut reader = Reader {
+            data: &data,
+            position: 0,
+        };
+
+        if !is_packed(&data) {
+            return Err(reader.error("not a packed mapping"));
+        }
+        reader.position = format::MAGIC.len();
+        let format_version = reader.byte()?;
+        if format_version != format::FORMAT_VERSION {
+            return Err(reader.error(format!(
+                "unsupported format version {}, expected {}",
+                format_version,
+                format::FORMAT_VERSION
+            )));
+        }
+
+        let version = match reader.byte()? {
+            0 => None,
+            _ => Some(reader.str()?.to_string()),
+        };
+        let namespace = match reader.str()? {
+            "official" => Namespace::Official,
+            "intermediary" => Namespace::Intermediary,
+            other => return Err(reader.error(format!("unknown namespace {}", other))),
+        };
+
+        // Counts are capped by the data length, a corrupted count fails instead of allocating
+        let count = reader.varint()?;
+        let mut strings = Vec::with_capacity(count.min(data.len()));
+        for _ in 0..count {
+            let len = reader.varint()?;
+            strings.push(reader.range(len)?);
+        }
+
+        let count = reader.varint()?;
+        let mut index = Vec::with_capacity(count.min(data.len()));
+        for _ in 0..count {
+            let name = reader.varint()?;
+            let runtime_name = reader.varint()?;
+            let len = reader.varint()?;
+            index.push((name, runtime_name, reader.range(len)?));
+        }
+        if reader.position != data.len() {
+            return Err(reader.error("trailing data"));
+        }
+
+        let mut packed = PackedClasses {
+            data,
+            strings,
+            classes: HashMap::with_capacity(index.len()),
+        };
+        let reader = Reader {
+            data: &packed.data,
+            position: 0,
+        };
+        for (name, runtime_name, body) in index {
+            let class = PackedClass {
+                name: packed.string(&reader, runtime_name)?.to_string(),
+                body,
+                decoded: OnceLock::new(),
+            };
+            let name = packed.string(&reader, name)?.to_string();
+            packed.classes.insert(name, class);
+        }
+
+        Ok(Mapping {
+            version,
+            namespace,
+            descriptors: DescriptorNames::Runtime,
+            classes: HashMap::new(),
+            packed: Some(packed),
+            cache: ResolutionCache::default(),
+            reverse: OnceLock::new(),
+            discovered: Default::default(),
+        })
+    }
+
+    /// Writes the mapping in the packed format, see [`Mapping::from_packed`]
+    pub fn to_packed(&self) -> Result<Vec<u8>, String> {
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        if let Some(packed) = &self.packed {
+            let classes = value["classes"].as_object_mut().unwrap();
+            for name in packed.classes.keys() {
+                if let Some((_, class)) = packed.get(name) {
+                    let class = serde_json::to_value(class).map_err(|e| e.to_string())?;
+                    classes.insert(name.clone(), class);
+                }
+            }
+        }
+        format::encode(&value)
+    }
+
+    /// Decodes every class of a packed mapping, needed before changing all of them
+    pub fn unpack(&mut self) {
+        let Some(mut packed) = self.packed.take() else {
+            return;
+        };
+        let names: Vec<String> = packed.classes.keys().cloned().collect();
+        for name in names {
+            if let Some(class) = packed.take(&name) {
+                self.classes.insert(name, class);
+            }
+        }
+    }
+
+    /// Returns the Mojang name and the class, decoding it if the mapping is packed
+    pub(crate) fn class_entry(&self, name: &str) -> Option<(&str, &MinecraftClass)> {
+        match self.classes.get_key_value(name) {
+            Some((name, class)) => Some((name.as_str(), class)),
+            None => self.packed.as_ref()?.get(name),
+        }
+    }
+
+    /// Returns a class to change, a packed class is decoded and moved out of the packed data
+    pub(crate) fn class_mut(&mut self, name: &str) -> Option<&mut MinecraftClass> {
+        if !self.classes.contains_key(name) {
+            let class = self.packed.as_mut()?.take(name)?;
+            self.classes.insert(name.to_string(), class);
+        }
+        self.classes.get_mut(name)
+    }
+
+    pub(crate) fn contains_class(&self, name: &str) -> bool {
+        self.classes.contains_key(name)
+            || self
+                .packed
+                .as_ref()
+                .is_some_and(|packed| packed.classes.contains_key(name))
+    }
+
+    /// Returns the runtime name of a class without decoding its members
+    pub(crate) fn runtime_name(&self, name: &str) -> Option<&str> {
+        match self.classes.get(name) {
+            Some(class) => Some(&class.name),
+            None => self
+                .packed
+                .as_ref()?
+                .classes
+                .get(name)
+                .map(|class| class.name.as_str()),
+        }
+    }
+
+    /// Returns the Mojang and runtime names of every class, without decoding their members
+    pub(crate) fn class_names(&self) -> impl Iterator<Item = (&str, &str)> {
+        let packed = self.packed.iter().flat_map(|packed| {
+            packed
+                .classes
+                .iter()
+                .map(|(name, class)| (name.as_str(), class.name.as_str()))
+        });
+        self.classes
+            .iter()
+            .map(|(name, class)| (name.as_str(), class.name.as_str()))
+            .chain(packed)
+    }
+
+    /// Returns every class with its Mojang name, decoding all the packed ones
+    pub fn classes(&self) -> impl Iterator<Item = (&str, &MinecraftClass)> {
+        let packed = self
+            .packed
+            .iter()
+            .flat_map(|packed| packed.classes.keys().filter_map(|name| packed.get(name)));
+        self.classes
+            .iter()
+            .map(|(name, class)| (name.as_str(), class))
+            .chain(packed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::MinecraftClassType;
+
+    const MAPPING: &str = r#"{
+        "version": "1.21.5",
+        "descriptors": "mojang",
+        "classes": {
+            "net/minecraft/world/entity/Entity": {
+                "name": "bxe",
+                "methods": {
+                    "position": {"name": "dt", "signature": "()Lnet/minecraft/world/phys/Vec3;"},
+                    "setPos": [
+                        {"name": "a", "signature": "(DDD)V"},
+                        {"name": "a", "signature": "(Lnet/minecraft/world/phys/Vec3;)V"}
+                    ]
+                },
+                "fields": {"fallDistance": {"name": "ac", "signature": "D"}, "level": {"name": "ad"}}
+            },
+            "net/minecraft/world/entity/player/Player": {
+                "name": "cpx",
+                "methods": {},
+                "fields": {},
+                "superclass": "net/minecraft/world/entity/Entity"
+            },
+            "net/minecraft/world/phys/Vec3": {"name": "fby", "methods": {}, "fields": {}}
+        }
+    }"#;
+
+    #[test]
+    fn test_packed_round_trip() {
+        let json = Mapping::from_json(MAPPING).unwrap();
+        let data = json.to_packed().unwrap();
+        assert!(is_packed(&data));
+        assert!(data.len() < MAPPING.len());
+
+        let mapping = Mapping::from_packed(data.clone()).unwrap();
+        assert_eq!(mapping.get_version(), Some("1.21.5"));
+        assert_eq!(
+            mapping.runtime_name("net/minecraft/world/phys/Vec3"),
+            Some("fby")
+        );
+        let decoded = |mapping: &Mapping| mapping.packed.as_ref().unwrap().decoded_count();
+        assert_eq!(decoded(&mapping), 0);
+        // The reverse index only reads class names
+        assert_eq!(
+            mapping.reverse().class_name("fby"),
+            Some("net/minecraft/world/phys/Vec3")
+        );
+        assert_eq!(decoded(&mapping), 0);
+
+        let set_pos = mapping
+            .resolve_method_by_signature(MinecraftClassType::Entity, "setPos", "(Lfby;)V")
+            .unwrap();
+        assert_eq!(set_pos.name, "a");
+        let position = mapping
+            .resolve_method(MinecraftClassType::Entity, "position", &[])
+            .unwrap();
+        assert_eq!(position.signature, "()Lfby;");
+        let fall_distance = mapping
+            .resolve_field(MinecraftClassType::Player, "fallDistance")
+            .unwrap();
+        assert_eq!(fall_distance.signature.as_deref(), Some("D"));
+        assert_eq!(decoded(&mapping), 2);
+        assert_eq!(
+            mapping
+                .resolve_field(MinecraftClassType::Entity, "level")
+                .unwrap()
+                .signature,
+            None
+        );
+
+        // Packing the packed mapping again gives the same bytes
+        assert_eq!(mapping.to_packed().unwrap(), data);
+        let mut unpacked = Mapping::from_packed(data.clone()).unwrap();
+        unpacked.unpack();
+        assert_eq!(unpacked.classes.len(), 3);
+        assert_eq!(unpacked.to_packed().unwrap(), data);
+    }
+
+    #[test]
+    fn test_invalid_packed_data() {
+        let data = Mapping::from_json(MAPPING).unwrap().to_packed().unwrap();
+
+        let error = Mapping::from_packed(data[..data.len() - 1].to_vec()).unwrap_err();
+        assert_eq!(error.message, "unexpected end of data");
+        assert!(Mapping::from_packed(MAPPING.as_bytes().to_vec()).is_err());
+
+        let mut newer = data.clone();
+        newer[format::MAGIC.len()] = format::FORMAT_VERSION + 1;
+        let error = Mapping::from_packed(newer).unwrap_err();
+        assert_eq!(error.offset, format::MAGIC.len() + 1);
+    }
+}
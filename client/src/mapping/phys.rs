@@ -0,0 +1,30 @@
+use crate::mapping::bindings::vec3;
+use crate::mapping::error::MappingError;
+use crate::mapping::java::{check_not_null, FromJava, IntoJava};
+use crate::mapping::Mapping;
+use jni::objects::JObject;
+
+/// Copy of a `net.minecraft.world.phys.Vec3`, which is immutable in the game
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl FromJava for Vec3 {
+    fn from_java(mapping: &Mapping, object: &JObject) -> Result<Self, MappingError> {
+        check_not_null(object, "Vec3")?;
+        Ok(Vec3 {
+            x: vec3::get_x(mapping, object)?,
+            y: vec3::get_y(mapping, object)?,
+            z: vec3::get_z(mapping, object)?,
+        })
+    }
+}
+
+impl IntoJava for Vec3 {
+    fn to_java<'a>(&self, mapping: &'a Mapping) -> Result<JObject<'a>, MappingError> {
+        vec3::new_3(mapping, self.x, self.y, self.z)
+    }
+}
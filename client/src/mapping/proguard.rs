@@ -0,0 +1,400 @@
+use crate::mapping::cache::ResolutionCache;
+use crate::mapping::descriptor::DescriptorNames;
+use crate::mapping::namespace::Namespace;
+use crate::mapping::{Field, Mapping, Method, MinecraftClass};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Error raised while parsing a ProGuard mapping, `line` is 1-based
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProGuardError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ProGuardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ProGuardError {}
+
+/// A class or member line of the mapping
+#[derive(Debug, PartialEq, Eq)]
+enum Entry<'a> {
+    Class {
+        name: &'a str,
+        obfuscated: &'a str,
+    },
+    Method {
+        return_type: &'a str,
+        name: &'a str,
+        parameters: Vec<&'a str>,
+        obfuscated: &'a str,
+    },
+    Field {
+        field_type: &'a str,
+        name: &'a str,
+        obfuscated: &'a str,
+    },
+}
+
+impl Mapping {
+    /// Parses a Mojang ProGuard mapping (`client.txt`), see [`parse`]
+    pub fn from_proguard(input: &str) -> Result<Mapping, ProGuardError> {
+        parse(input)
+    }
+}
+
+/// Parses a Mojang ProGuard mapping (`client.txt`) into a [`Mapping`]
+///
+/// Class names are converted to the JVM internal form (`net/minecraft/client/Minecraft`),
+/// method signatures are converted to JNI descriptors using the obfuscated class names
+/// and overloads are kept as separate methods under the same name.
+pub fn parse(input: &str) -> Result<Mapping, ProGuardError> {
+    let entries = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.trim();
+            !line.is_empty() && !line.starts_with('#')
+        })
+        .map(|(index, line)| {
+            parse_line(line)
+                .map(|entry| (index + 1, entry))
+                .map_err(|message| ProGuardError {
+                    line: index + 1,
+                    message,
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // First pass: collect the class names, the signatures need the obfuscated ones
+    let class_map: HashMap<String, String> = entries
+        .iter()
+        .filter_map(|(_, entry)| match entry {
+            Entry::Class { name, obfuscated } => {
+                Some((to_internal_name(name), to_internal_name(obfuscated)))
+            }
+            _ => None,
+        })
+        .collect();
+
+    // Second pass: attach methods and fields to their class
+    let mut classes: HashMap<String, MinecraftClass> = HashMap::new();
+    let mut current_class = None;
+
+    for (line, entry) in entries {
+        match entry {
+            Entry::Class { name, obfuscated } => {
+                let name = to_internal_name(name);
+                classes
+                    .entry(name.clone())
+                    .or_insert_with(|| MinecraftClass {
+                        name: to_internal_name(obfuscated),
+                        methods: HashMap::new(),
+                        fields: HashMap::new(),
+                        superclass: None,
+                        interfaces: Vec::new(),
+                    });
+                current_class = Some(name);
+            }
+            Entry::Method {
+                return_type,
+                name,
+                parameters,
+                obfuscated,
+            } => {
+                let class = current_class
+                    .as_ref()
+                    .and_then(|class| classes.get_mut(class))
+                    .ok_or_else(|| ProGuardError {
+                        line,
+                        message: format!("method {} outside of a class", name),
+                    })?;
+
+                let signature = format!(
+                    "({}){}",
+                    parameters
+                        .iter()
+                        .map(|parameter| java_type_to_descriptor(parameter, &class_map))
+                        .collect::<String>(),
+                    java_type_to_descriptor(return_type, &class_map)
+                );
+
+                // Inlined methods are listed once per line range, keep a single copy
+                let overloads = class.methods.entry(name.to_string()).or_default();
+                if !overloads
+                    .iter()
+                    .any(|method| method.name == obfuscated && method.signature == signature)
+                {
+                    overloads.push(Method {
+                        name: obfuscated.to_string(),
+                        signature,
+                    });
+                }
+            }
+            Entry::Field {
+                field_type,
+                name,
+                obfuscated,
+            } => {
+                let class = current_class
+                    .as_ref()
+                    .and_then(|class| classes.get_mut(class))
+                    .ok_or_else(|| ProGuardError {
+                        line,
+                        message: format!("field {} outside of a class", name),
+                    })?;
+
+                class.fields.insert(
+                    name.to_string(),
+                    Field {
+                        name: obfuscated.to_string(),
+                        signature: Some(java_type_to_descriptor(field_type, &class_map)),
+                    },
+                );
+            }
+        }
+    }
+
+    Ok(Mapping {
+        version: None,
+        namespace: Namespace::Official,
+        descriptors: DescriptorNames::Runtime,
+        classes,
+        packed: None,
+        cache: ResolutionCache::default(),
+        reverse: OnceLock::new(),
+        discovered: Default::default(),
+    })
+}
+
+fn parse_line(line: &str) -> Result<Entry<'_>, String> {
+    let is_member = line.starts_with(char::is_whitespace);
+    let line = line.trim();
+
+    if !is_member {
+        // net.minecraft.client.Minecraft -> fzz:
+        let (name, obfuscated) = line
+            .strip_suffix(':')
+            .and_then(|line| line.split_once(" -> "))
+            .ok_or_else(|| format!("invalid class line '{}'", line))?;
+        return Ok(Entry::Class { name, obfuscated });
+    }
+
+    // [1:3:]type name[(parameters)[:1:3]] -> obfuscated
+    let (member, obfuscated) = line
+        .split_once(" -> ")
+        .ok_or_else(|| format!("invalid member line '{}'", line))?;
+    let member = strip_line_numbers(member);
+    let (member_type, rest) = member
+        .split_once(' ')
+        .ok_or_else(|| format!("missing member type in '{}'", line))?;
+
+    match rest.find('(') {
+        Some(open) => {
+            let close = rest
+                .find(')')
+                .filter(|close| *close > open)
+                .ok_or_else(|| format!("invalid parameter list in '{}'", line))?;
+            let parameters = rest[open + 1..close]
+                .split(',')
+                .map(str::trim)
+                .filter(|parameter| !parameter.is_empty())
+                .collect();
+
+            Ok(Entry::Method {
+                return_type: member_type,
+                name: &rest[..open],
+                parameters,
+                obfuscated,
+            })
+        }
+        None => Ok(Entry::Field {
+            field_type: member_type,
+            name: rest,
+            obfuscated,
+        }),
+    }
+}
+
+/// Removes the `start:end:` line number prefix of a method
+fn strip_line_numbers(member: &str) -> &str {
+    let mut parts = member.splitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(start), Some(end), Some(rest)) if is_line_number(start) && is_line_number(end) => {
+            rest
+        }
+        _ => member,
+    }
+}
+
+fn is_line_number(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|ch| ch.is_ascii_digit())
+}
+
+fn to_internal_name(name: &str) -> String {
+    name.replace('.', "/")
+}
+
+/// Converts a Java source type to a JNI descriptor, remapping classes through `class_map`
+///
+/// # Example
+/// `java.lang.String[]` -> `[Ljava/lang/String;`
+pub fn java_type_to_descriptor(java_type: &str, class_map: &HashMap<String, String>) -> String {
+    let mut element_type = java_type.trim();
+    let mut descriptor = String::new();
+    while let Some(stripped) = element_type.strip_suffix("[]") {
+        descriptor.push('[');
+        element_type = stripped;
+    }
+
+    match element_type {
+        "void" => descriptor.push('V'),
+        "boolean" => descriptor.push('Z'),
+        "byte" => descriptor.push('B'),
+        "char" => descriptor.push('C'),
+        "short" => descriptor.push('S'),
+        "int" => descriptor.push('I'),
+        "float" => descriptor.push('F'),
+        "long" => descriptor.push('J'),
+        "double" => descriptor.push('D'),
+        class_name => {
+            let internal_name = to_internal_name(class_name);
+            let class_name = class_map.get(&internal_name).unwrap_or(&internal_name);
+            descriptor.push('L');
+            descriptor.push_str(class_name);
+            descriptor.push(';');
+        }
+    }
+
+    descriptor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# {\"id\":\"com.android.tools.r8.mapping\",\"version\":\"2.2\"}
+net.minecraft.client.Minecraft -> fzz:
+# {\"fileName\":\"Minecraft.java\",\"id\":\"sourceFile\"}
+    net.minecraft.client.player.LocalPlayer player -> t
+    int[][] lightLevels -> u
+    1:3:void <init>(net.minecraft.client.main.GameConfig) -> <init>
+    12:12:net.minecraft.client.Minecraft getInstance() -> R
+    20:21:void setScreen(net.minecraft.client.gui.screens.Screen) -> a
+    22:22:void setScreen(net.minecraft.client.gui.screens.Screen):100:100 -> a
+    30:30:void setScreen(net.minecraft.client.gui.screens.Screen,boolean) -> a
+    java.lang.String[] splitName(java.lang.String,char[]) -> b
+net.minecraft.client.Minecraft$ChatStatus -> fzz$a:
+    net.minecraft.client.Minecraft$ChatStatus ENABLED -> a
+    1:1:boolean isChatAllowed(boolean) -> a
+net.minecraft.client.player.LocalPlayer -> gkv:
+net.minecraft.client.main.GameConfig -> gmb:
+";
+
+    #[test]
+    fn test_parse_classes() {
+        let mapping = parse(SAMPLE).unwrap();
+
+        assert_eq!(
+            mapping.get_class("net/minecraft/client/Minecraft").name,
+            "fzz"
+        );
+        assert_eq!(
+            mapping
+                .get_class("net/minecraft/client/Minecraft$ChatStatus")
+                .name,
+            "fzz$a"
+        );
+        assert_eq!(mapping.classes.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_members() {
+        let mapping = parse(SAMPLE).unwrap();
+        let minecraft = mapping.get_class("net/minecraft/client/Minecraft");
+
+        assert_eq!(minecraft.get_field("player").name, "t");
+        assert_eq!(
+            minecraft.get_field("player").signature.as_deref(),
+            Some("Lgkv;")
+        );
+        assert_eq!(minecraft.get_field("lightLevels").name, "u");
+        assert_eq!(
+            minecraft.get_field("lightLevels").signature.as_deref(),
+            Some("[[I")
+        );
+
+        let get_instance = minecraft.get_method("getInstance");
+        assert_eq!(get_instance.name, "R");
+        assert_eq!(get_instance.signature, "()Lfzz;");
+
+        let constructor = minecraft.get_method("<init>");
+        assert_eq!(constructor.signature, "(Lgmb;)V");
+
+        let chat_status = mapping.get_class("net/minecraft/client/Minecraft$ChatStatus");
+        assert_eq!(chat_status.get_field("ENABLED").name, "a");
+        assert_eq!(chat_status.get_method("isChatAllowed").signature, "(Z)Z");
+    }
+
+    #[test]
+    fn test_parse_overloads() {
+        let mapping = parse(SAMPLE).unwrap();
+        let minecraft = mapping.get_class("net/minecraft/client/Minecraft");
+
+        // The inlined duplicate of setScreen(Screen) is only kept once
+        let signatures: Vec<&str> = minecraft
+            .get_methods("setScreen")
+            .iter()
+            .map(|method| method.signature.as_str())
+            .collect();
+        assert_eq!(
+            signatures,
+            vec![
+                "(Lnet/minecraft/client/gui/screens/Screen;)V",
+                "(Lnet/minecraft/client/gui/screens/Screen;Z)V"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_arrays() {
+        let mapping = parse(SAMPLE).unwrap();
+        let minecraft = mapping.get_class("net/minecraft/client/Minecraft");
+
+        assert_eq!(
+            minecraft.get_method("splitName").signature,
+            "(Ljava/lang/String;[C)[Ljava/lang/String;"
+        );
+    }
+
+    #[test]
+    fn test_type_conversion() {
+        let class_map = HashMap::from([(
+            "net/minecraft/world/phys/Vec3".to_string(),
+            "fby".to_string(),
+        )]);
+
+        assert_eq!(java_type_to_descriptor("double", &class_map), "D");
+        assert_eq!(java_type_to_descriptor("int[][]", &class_map), "[[I");
+        assert_eq!(
+            java_type_to_descriptor("net.minecraft.world.phys.Vec3[]", &class_map),
+            "[Lfby;"
+        );
+        assert_eq!(
+            java_type_to_descriptor("java.util.List", &class_map),
+            "Ljava/util/List;"
+        );
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let error = parse("net.minecraft.client.Minecraft -> fzz:\n    int broken\n").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+}
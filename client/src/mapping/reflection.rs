@@ -0,0 +1,259 @@
+use crate::mapping::check::member_descriptor;
+use crate::mapping::error::MappingError;
+use crate::mapping::{Field, Mapping, Method, MinecraftClass, MinecraftClassType, CONSTRUCTOR};
+use jni::objects::{JClass, JObject, JObjectArray, JString, JValue};
+use jni::JNIEnv;
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Class whose members are looked up, from the mapping or read through reflection for unmapped handles
+pub enum ClassRef<'a> {
+    Mapped(&'a MinecraftClass),
+    Reflected(Arc<MinecraftClass>),
+}
+
+impl Deref for ClassRef<'_> {
+    type Target = MinecraftClass;
+
+    fn deref(&self) -> &MinecraftClass {
+        match self {
+            ClassRef::Mapped(class) => class,
+            ClassRef::Reflected(class) => class,
+        }
+    }
+}
+
+impl Mapping {
+    /// Returns the mapped class of a handle, or its members read through reflection if it is unmapped
+    pub fn class_ref(
+        &self,
+        env: &mut JNIEnv,
+        class_type: MinecraftClassType,
+    ) -> Result<ClassRef<'_>, MappingError> {
+        if class_type.is_mapped() {
+            return self
+                .try_get_class(class_type.get_name())
+                .map(ClassRef::Mapped);
+        }
+
+        self.cache
+            .reflected_class(env, class_type)
+            .map(ClassRef::Reflected)
+            .map_err(|e| self.java_error(env, e))
+    }
+
+    /// Like [`Mapping::resolve_method`], unmapped classes are searched with their inherited members
+    pub fn resolve_method_in<'a>(
+        &'a self,
+        class_type: MinecraftClassType,
+        class: &'a ClassRef,
+        method_name: &str,
+        args: &[JValue],
+    ) -> Result<&'a Method, MappingError> {
+        match class {
+            ClassRef::Mapped(_) => self.resolve_method(class_type, method_name, args),
+            ClassRef::Reflected(class) => {
+                self.select_method(class_type, class, method_name, args, |name| {
+                    class.find_methods(name).into_iter().flatten().collect()
+                })
+            }
+        }
+    }
+
+    /// Like [`Mapping::resolve_field`], for mapped or unmapped classes
+    pub fn resolve_field_in<'a>(
+        &'a self,
+        class_type: MinecraftClassType,
+        class: &'a ClassRef,
+        field_name: &str,
+    ) -> Result<&'a Field, MappingError> {
+        match class {
+            ClassRef::Mapped(_) => self.resolve_field(class_type, field_name),
+            ClassRef::Reflected(class) => {
+                class
+                    .find_field(field_name)
+                    .ok_or_else(|| MappingError::MissingField {
+                        class: class_type.get_name().to_string(),
+                        field: field_name.to_string(),
+                    })
+            }
+        }
+    }
+}
+
+/// Reads the members of a class and of its superclasses, keyed by their runtime names
+///
+/// Public interface methods are included too, overridden members are kept once.
+pub fn reflect_class(
+    env: &mut JNIEnv,
+    class: &JClass,
+    name: &str,
+) -> jni::errors::Result<MinecraftClass> {
+    let mut methods: HashMap<String, Vec<Method>> = HashMap::new();
+    let mut fields: HashMap<String, Field> = HashMap::new();
+
+    env.with_local_frame(16, |env| {
+        read_members(
+            env,
+            class,
+            "getDeclaredConstructors",
+            "()[Ljava/lang/reflect/Constructor;",
+            |_, signature| add_method(&mut methods, CONSTRUCTOR, signature),
+        )?;
+
+        let mut current = env.new_local_ref(class)?;
+        while !current.is_null() {
+            read_members(
+                env,
+                &current,
+                "getDeclaredMethods",
+                "()[Ljava/lang/reflect/Method;",
+                |name, signature| add_method(&mut methods, &name, signature),
+            )?;
+            read_members(
+                env,
+                &current,
+                "getDeclaredFields",
+                "()[Ljava/lang/reflect/Field;",
+                |name, signature| {
+                    fields.entry(name.clone()).or_insert(Field {
+                        name,
+                        signature: Some(signature),
+                    });
+                },
+            )?;
+
+            let superclass = env
+                .call_method(&current, "getSuperclass", "()Ljava/lang/Class;", &[])?
+                .l()?;
+            env.delete_local_ref(current)?;
+            current = superclass;
+        }
+
+        // Default methods of the interfaces aren't declared by any class of the chain
+        read_members(
+            env,
+            class,
+            "getMethods",
+            "()[Ljava/lang/reflect/Method;",
+            |name, signature| add_method(&mut methods, &name, signature),
+        )
+    })?;
+
+    Ok(MinecraftClass {
+        name: name.to_string(),
+        methods,
+        fields,
+        superclass: None,
+        interfaces: Vec::new(),
+    })
+}
+
+/// Calls a `java.lang.Class` getter returning members and passes the name and descriptor of each one
+fn read_members(
+    env: &mut JNIEnv,
+    class: &JObject,
+    getter: &str,
+    array_type: &str,
+    mut add: impl FnMut(String, String),
+) -> jni::errors::Result<()> {
+    let is_method = !array_type.ends_with("Field;");
+    let members = JObjectArray::from(env.call_method(class, getter, array_type, &[])?.l()?);
+
+    for index in 0..env.get_array_length(&members)? {
+        env.with_local_frame(16, |env| -> jni::errors::Result<()> {
+            let member = env.get_object_array_element(&members, index)?;
+            let name = env
+                .call_method(&member, "getName", "()Ljava/lang/String;", &[])?
+                .l()?;
+            let name: String = env.get_string(&JString::from(name))?.into();
+            add(name, member_descriptor(env, &member, is_method)?);
+            Ok(())
+        })?;
+    }
+
+    env.delete_local_ref(members)
+}
+
+fn add_method(methods: &mut HashMap<String, Vec<Method>>, name: &str, signature: String) {
+    let overloads = methods.entry(name.to_string()).or_default();
+    if !overloads.iter().any(|method| method.signature == signature) {
+        overloads.push(Method {
+            name: name.to_string(),
+            signature,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reflected_members() {
+        const GLFW: MinecraftClassType = MinecraftClassType::unmapped("org/lwjgl/glfw/GLFW");
+        let mapping: Mapping = serde_json::from_str(r#"{"classes": {}}"#).unwrap();
+
+        let mut methods = HashMap::new();
+        add_method(&mut methods, "glfwGetKey", "(JI)I".to_string());
+        add_method(&mut methods, "glfwSetCursorPos", "(JDD)V".to_string());
+        add_method(&mut methods, "glfwSetCursorPos", "(JDD)V".to_string());
+        add_method(
+            &mut methods,
+            "glfwSetCursorPos",
+            "(JLjava/nio/DoubleBuffer;)V".to_string(),
+        );
+        let mut fields = HashMap::new();
+        fields.insert(
+            "GLFW_PRESS".to_string(),
+            Field {
+                name: "GLFW_PRESS".to_string(),
+                signature: Some("I".to_string()),
+            },
+        );
+        let class = ClassRef::Reflected(Arc::new(MinecraftClass {
+            name: GLFW.get_name().to_string(),
+            methods,
+            fields,
+            superclass: None,
+            interfaces: Vec::new(),
+        }));
+
+        assert_eq!(class.find_methods("glfwSetCursorPos").unwrap().len(), 2);
+        let get_key = mapping
+            .resolve_method_in(
+                GLFW,
+                &class,
+                "glfwGetKey",
+                &[JValue::Long(1), JValue::Int(32)],
+            )
+            .unwrap();
+        assert_eq!(get_key.signature, "(JI)I");
+        let set_cursor_pos = mapping
+            .resolve_method_in(
+                GLFW,
+                &class,
+                "glfwSetCursorPos",
+                &[JValue::Long(1), JValue::Double(0.0), JValue::Double(0.0)],
+            )
+            .unwrap();
+        assert_eq!(set_cursor_pos.signature, "(JDD)V");
+        assert_eq!(
+            mapping
+                .resolve_field_in(GLFW, &class, "GLFW_PRESS")
+                .unwrap()
+                .signature
+                .as_deref(),
+            Some("I")
+        );
+        assert!(matches!(
+            mapping.resolve_field_in(GLFW, &class, "GLFW_RELEASE"),
+            Err(MappingError::MissingField { .. })
+        ));
+        assert_eq!(
+            crate::mapping::FieldType::Object(GLFW, &mapping).get_signature(),
+            "Lorg/lwjgl/glfw/GLFW;"
+        );
+    }
+}
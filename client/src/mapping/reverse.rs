@@ -0,0 +1,250 @@
+use crate::mapping::descriptor::remap_descriptor;
+use crate::mapping::error::{MappingError, StackFrame};
+use crate::mapping::{Mapping, MinecraftClass};
+use jni::JNIEnv;
+use std::collections::HashMap;
+
+/// Mojang names of the runtime classes, used to make errors and stack traces readable
+///
+/// Only the class names are indexed, so building it doesn't decode a packed mapping. Members are looked up
+/// in their class through [`ReverseNames`].
+#[derive(Debug, Default)]
+pub struct ReverseIndex {
+    classes: HashMap<String, String>,
+}
+
+impl ReverseIndex {
+    pub fn new(mapping: &Mapping) -> Self {
+        let classes = mapping
+            .class_names()
+            .map(|(name, runtime_name)| (runtime_name.to_string(), name.to_string()))
+            .collect();
+        ReverseIndex { classes }
+    }
+}
+
+/// Lookups from runtime names to Mojang names, returned by [`Mapping::reverse`]
+///
+/// The members of a packed class are decoded only when one of them is looked up.
+#[derive(Debug, Clone, Copy)]
+pub struct ReverseNames<'a> {
+    mapping: &'a Mapping,
+    index: &'a ReverseIndex,
+}
+
+impl<'a> ReverseNames<'a> {
+    /// Returns the Mojang name of a runtime class name, e.g. `fzz` -> `net/minecraft/client/Minecraft`
+    pub fn class_name(&self, runtime_name: &str) -> Option<&'a str> {
+        self.index.classes.get(runtime_name).map(String::as_str)
+    }
+
+    fn class(&self, runtime_class: &str) -> Option<&'a MinecraftClass> {
+        let name = self.class_name(runtime_class)?;
+        self.mapping.class_entry(name).map(|(_, class)| class)
+    }
+
+    /// Returns the Mojang names of the methods with this runtime name, more than one if
+    /// overloads of different methods got the same name
+    pub fn method_names(&self, runtime_class: &str, runtime_name: &str) -> Vec<&'a str> {
+        let Some(class) = self.class(runtime_class) else {
+            return Vec::new();
+        };
+        class
+            .methods
+            .iter()
+            .filter(|(_, overloads)| overloads.iter().any(|method| method.name == runtime_name))
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Returns the Mojang name of the method with this runtime name and descriptor
+    pub fn method_name(
+        &self,
+        runtime_class: &str,
+        runtime_name: &str,
+        signature: &str,
+    ) -> Option<&'a str> {
+        self.class(runtime_class)?
+            .methods
+            .iter()
+            .find(|(_, overloads)| {
+                overloads
+                    .iter()
+                    .any(|method| method.name == runtime_name && method.signature == signature)
+            })
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn field_name(&self, runtime_class: &str, runtime_name: &str) -> Option<&'a str> {
+        self.class(runtime_class)?
+            .fields
+            .iter()
+            .find(|(_, field)| field.name == runtime_name)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Replaces the runtime class names of a descriptor with the Mojang ones
+    pub fn deobfuscate_descriptor(&self, descriptor: &str) -> String {
+        remap_descriptor(descriptor, |runtime_name| self.class_name(runtime_name))
+    }
+
+    /// Converts a Java class name as shown in stack traces, e.g. `fzz$a`, to its Mojang name
+    ///
+    /// Unmapped nested classes keep their suffix after the mapped outer class.
+    pub fn java_class_name(&self, java_name: &str) -> Option<String> {
+        let runtime_name = java_name.replace('.', "/");
+        if let Some(name) = self.class_name(&runtime_name) {
+            return Some(name.replace('/', "."));
+        }
+
+        let (outer, inner) = runtime_name.split_once('$')?;
+        let outer = self.class_name(outer)?;
+        Some(format!("{}${}", outer, inner).replace('/', "."))
+    }
+
+    /// Returns the frame with Mojang class and method names, overloads that share a runtime name
+    /// are joined with `|`
+    pub fn deobfuscate_frame(&self, frame: &StackFrame) -> StackFrame {
+        let runtime_class = frame.class.replace('.', "/");
+        let method_names = self.method_names(&runtime_class, &frame.method);
+
+        StackFrame {
+            class: self
+                .java_class_name(&frame.class)
+                .unwrap_or_else(|| frame.class.clone()),
+            method: if method_names.is_empty() {
+                frame.method.clone()
+            } else {
+                method_names.join("|")
+            },
+            file: frame.file.clone(),
+            line: frame.line,
+        }
+    }
+
+    /// Replaces the runtime names of a Java exception and its stack trace with the Mojang ones
+    pub fn deobfuscate_error(&self, error: MappingError) -> MappingError {
+        match error {
+            MappingError::JavaException {
+                class,
+                message,
+                stack_trace,
+            } => MappingError::JavaException {
+                class: self.java_class_name(&class).unwrap_or(class),
+                message,
+                stack_trace: stack_trace
+                    .iter()
+                    .map(|frame| self.deobfuscate_frame(frame))
+                    .collect(),
+            },
+            error => error,
+        }
+    }
+}
+
+impl Mapping {
+    /// Returns the lookups from runtime names to Mojang names, the class index is built on first use
+    pub fn reverse(&self) -> ReverseNames<'_> {
+        ReverseNames {
+            mapping: self,
+            index: self.reverse.get_or_init(|| ReverseIndex::new(self)),
+        }
+    }
+
+    /// Clears the pending Java exception and returns it with a deobfuscated stack trace, `None` if there is none
+    pub fn take_exception(&self, env: &mut JNIEnv) -> Option<MappingError> {
+        MappingError::take_exception(env).map(|error| self.reverse().deobfuscate_error(error))
+    }
+
+    /// Converts a JNI error like [`MappingError::from_jni`], deobfuscating the thrown exception
+    pub fn java_error(&self, env: &mut JNIEnv, error: jni::errors::Error) -> MappingError {
+        self.reverse()
+            .deobfuscate_error(MappingError::from_jni(env, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> Mapping {
+        serde_json::from_str(
+            r#"{
+                "classes": {
+                    "net/minecraft/client/Minecraft": {
+                        "name": "fzz",
+                        "methods": {
+                            "tick": {"name": "a", "signature": "()V"},
+                            "setLevel": {"name": "a", "signature": "(Lgga;)V"},
+                            "runTick": {"name": "f", "signature": "(Z)V"}
+                        },
+                        "fields": {"player": {"name": "t", "signature": "Lgkv;"}}
+                    },
+                    "net/minecraft/client/multiplayer/ClientLevel": {"name": "gga", "methods": {}, "fields": {}}
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_reverse_names() {
+        let mapping = mapping();
+        let reverse = mapping.reverse();
+
+        assert_eq!(
+            reverse.class_name("fzz"),
+            Some("net/minecraft/client/Minecraft")
+        );
+        assert_eq!(reverse.field_name("fzz", "t"), Some("player"));
+        assert_eq!(
+            reverse.method_name("fzz", "a", "(Lgga;)V"),
+            Some("setLevel")
+        );
+        let mut names = reverse.method_names("fzz", "a");
+        names.sort();
+        assert_eq!(names, vec!["setLevel", "tick"]);
+        assert_eq!(
+            reverse.deobfuscate_descriptor("(Lgga;I)V"),
+            "(Lnet/minecraft/client/multiplayer/ClientLevel;I)V"
+        );
+        assert_eq!(reverse.class_name("java/lang/String"), None);
+    }
+
+    #[test]
+    fn test_deobfuscate_stack_trace() {
+        let mapping = mapping();
+        let error = MappingError::JavaException {
+            class: "java.lang.IllegalStateException".to_string(),
+            message: Some("Not on the render thread".to_string()),
+            stack_trace: vec![
+                StackFrame {
+                    class: "fzz".to_string(),
+                    method: "f".to_string(),
+                    file: Some("SourceFile".to_string()),
+                    line: 1234,
+                },
+                StackFrame {
+                    class: "fzz$a".to_string(),
+                    method: "run".to_string(),
+                    file: None,
+                    line: -1,
+                },
+                StackFrame {
+                    class: "java.lang.Thread".to_string(),
+                    method: "run".to_string(),
+                    file: Some("Thread.java".to_string()),
+                    line: 1583,
+                },
+            ],
+        };
+
+        assert_eq!(
+            mapping.reverse().deobfuscate_error(error).to_string(),
+            "java.lang.IllegalStateException: Not on the render thread\n\
+             \tat net.minecraft.client.Minecraft.runTick(SourceFile:1234)\n\
+             \tat net.minecraft.client.Minecraft$a.run(Unknown Source)\n\
+             \tat java.lang.Thread.run(Thread.java:1583)"
+        );
+    }
+}
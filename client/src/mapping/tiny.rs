@@ -0,0 +1,406 @@
+use crate::mapping::descriptor::remap_descriptor;
+use crate::mapping::namespace::Namespace;
+use crate::mapping::Mapping;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Error raised while parsing or applying a Tiny mapping, `line` is 1-based and 0 when not tied to a line
+#[derive(Debug, PartialEq, Eq)]
+pub struct TinyError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for TinyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+impl std::error::Error for TinyError {}
+
+/// Tiny v2 mapping (Fabric Intermediary or Yarn), with one name per namespace for every entry
+#[derive(Debug, Default)]
+pub struct TinyMapping {
+    namespaces: Vec<String>,
+    classes: Vec<TinyClass>,
+}
+
+#[derive(Debug)]
+struct TinyClass {
+    names: Vec<String>,
+    fields: Vec<TinyMember>,
+    methods: Vec<TinyMember>,
+}
+
+/// Field or method, the descriptor uses the class names of the first namespace
+#[derive(Debug)]
+struct TinyMember {
+    descriptor: String,
+    names: Vec<String>,
+}
+
+/// Returns the name in the given namespace, empty names fall back to the first namespace
+fn name_in(names: &[String], namespace: usize) -> &str {
+    match names.get(namespace) {
+        Some(name) if !name.is_empty() => name,
+        _ => &names[0],
+    }
+}
+
+impl TinyClass {
+    fn name(&self, namespace: usize) -> &str {
+        name_in(&self.names, namespace)
+    }
+}
+
+impl TinyMember {
+    fn name(&self, namespace: usize) -> &str {
+        name_in(&self.names, namespace)
+    }
+}
+
+impl TinyMapping {
+    /// Parses a Tiny v2 file, parameters, local variables and comments are skipped
+    pub fn parse(input: &str) -> Result<TinyMapping, TinyError> {
+        let mut lines = input.lines().enumerate();
+        let error = |line: usize, message: String| TinyError {
+            line: line + 1,
+            message,
+        };
+
+        let (_, header) = lines
+            .next()
+            .ok_or_else(|| error(0, "empty file".to_string()))?;
+        let header: Vec<&str> = header.split('\t').collect();
+        if header.len() < 5 || header[0] != "tiny" || header[1] != "2" {
+            return Err(error(0, "not a Tiny v2 file".to_string()));
+        }
+
+        let mut mapping = TinyMapping {
+            namespaces: header[3..].iter().map(|name| name.to_string()).collect(),
+            classes: Vec::new(),
+        };
+        let name_count = mapping.namespaces.len();
+        let mut escaped_names = false;
+
+        for (index, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let depth = line.chars().take_while(|ch| *ch == '\t').count();
+            let columns: Vec<&str> = line[depth..].split('\t').collect();
+            let names = |columns: &[&str]| -> Result<Vec<String>, TinyError> {
+                if columns.len() != name_count {
+                    return Err(error(
+                        index,
+                        format!("expected {} names, found {}", name_count, columns.len()),
+                    ));
+                }
+                Ok(columns
+                    .iter()
+                    .map(|name| {
+                        if escaped_names {
+                            unescape(name)
+                        } else {
+                            name.to_string()
+                        }
+                    })
+                    .collect())
+            };
+
+            match (depth, columns[0]) {
+                // Header properties
+                (1, _) if mapping.classes.is_empty() => {
+                    if columns[0] == "escaped-names" {
+                        escaped_names = true;
+                    }
+                }
+                (0, "c") => mapping.classes.push(TinyClass {
+                    names: names(&columns[1..])?,
+                    fields: Vec::new(),
+                    methods: Vec::new(),
+                }),
+                (1, kind @ ("f" | "m")) => {
+                    let class = mapping
+                        .classes
+                        .last_mut()
+                        .ok_or_else(|| error(index, "member outside of a class".to_string()))?;
+                    let descriptor = columns
+                        .get(1)
+                        .ok_or_else(|| error(index, "missing member descriptor".to_string()))?;
+                    let member = TinyMember {
+                        descriptor: descriptor.to_string(),
+                        names: names(&columns[2..])?,
+                    };
+
+                    if kind == "f" {
+                        class.fields.push(member);
+                    } else {
+                        class.methods.push(member);
+                    }
+                }
+                // Class comments, parameters, local variables and member comments
+                (1, "c") | (2.., _) => {}
+                _ => return Err(error(index, format!("unexpected entry '{}'", line.trim()))),
+            }
+        }
+
+        Ok(mapping)
+    }
+
+    pub fn namespaces(&self) -> &[String] {
+        &self.namespaces
+    }
+
+    fn namespace_index(&self, namespace: Namespace) -> Result<usize, TinyError> {
+        self.namespaces
+            .iter()
+            .position(|name| name == namespace.tiny_name())
+            .ok_or_else(|| TinyError {
+                line: 0,
+                message: format!("missing {} namespace", namespace),
+            })
+    }
+
+    /// Maps a class name between two namespaces
+    pub fn map_class(&self, name: &str, from: Namespace, to: Namespace) -> Option<&str> {
+        let from = self.namespace_index(from).ok()?;
+        let to = self.namespace_index(to).ok()?;
+        self.classes
+            .iter()
+            .find(|class| class.name(from) == name)
+            .map(|class| class.name(to))
+    }
+}
+
+fn unescape(name: &str) -> String {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('0') => unescaped.push('\0'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+impl Mapping {
+    pub fn get_namespace(&self) -> Namespace {
+        self.namespace
+    }
+
+    /// Rewrites the runtime names of every class, method, field and signature into `target`
+    ///
+    /// The keys (Mojang names) are kept, so lookups through `MinecraftClassType` are unchanged.
+    /// Entries missing from the Tiny mapping keep their current name.
+    pub fn remap_namespace(
+        &mut self,
+        tiny: &TinyMapping,
+        target: Namespace,
+    ) -> Result<(), TinyError> {
+        if self.namespace == target {
+            return Ok(());
+        }
+
+        let from = tiny.namespace_index(self.namespace)?;
+        let to = tiny.namespace_index(target)?;
+
+        let tiny_classes: HashMap<&str, &TinyClass> = tiny
+            .classes
+            .iter()
+            .map(|class| (class.name(from), class))
+            .collect();
+        // Tiny descriptors use the first namespace, ours use the current one
+        let to_descriptor_namespace =
+            |name: &str| tiny_classes.get(name).map(|class| class.name(0));
+        let to_target = |name: &str| tiny_classes.get(name).map(|class| class.name(to));
+
+        self.unpack();
+        for class in self.classes.values_mut() {
+            if let Some(tiny_class) = tiny_classes.get(class.name.as_str()) {
+                let tiny_methods: HashMap<(&str, &str), &TinyMember> = tiny_class
+                    .methods
+                    .iter()
+                    .map(|method| ((method.name(from), method.descriptor.as_str()), method))
+                    .collect();
+
+                for method in class.methods.values_mut().flatten() {
+                    let descriptor = remap_descriptor(&method.signature, to_descriptor_namespace);
+                    if let Some(tiny_method) =
+                        tiny_methods.get(&(method.name.as_str(), descriptor.as_str()))
+                    {
+                        method.name = tiny_method.name(to).to_string();
+                    }
+                }
+
+                let tiny_fields: HashMap<(&str, &str), &TinyMember> = tiny_class
+                    .fields
+                    .iter()
+                    .map(|field| ((field.name(from), field.descriptor.as_str()), field))
+                    .collect();
+
+                for field in class.fields.values_mut() {
+                    let tiny_field = match &field.signature {
+                        Some(signature) => {
+                            let descriptor = remap_descriptor(signature, to_descriptor_namespace);
+                            tiny_fields
+                                .get(&(field.name.as_str(), descriptor.as_str()))
+                                .copied()
+                        }
+                        // Without a descriptor only a name shared by no other field is matched
+                        None => {
+                            let mut named = tiny_class
+                                .fields
+                                .iter()
+                                .filter(|tiny_field| tiny_field.name(from) == field.name);
+                            named.next().filter(|_| named.next().is_none())
+                        }
+                    };
+                    if let Some(tiny_field) = tiny_field {
+                        field.name = tiny_field.name(to).to_string();
+                    }
+                }
+
+                class.name = tiny_class.name(to).to_string();
+            }
+
+            for method in class.methods.values_mut().flatten() {
+                method.signature = remap_descriptor(&method.signature, to_target);
+            }
+            for field in class.fields.values_mut() {
+                if let Some(signature) = &field.signature {
+                    field.signature = Some(remap_descriptor(signature, to_target));
+                }
+            }
+        }
+
+        self.namespace = target;
+        self.reverse = OnceLock::new();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TINY: &str = "tiny\t2\t0\tofficial\tintermediary\tnamed
+\tescaped-names
+c\tfzz\tnet/minecraft/class_310\tnet/minecraft/client/MinecraftClient
+\tc\tThe game client
+\tf\tI\tt\tfield_1699\tfps
+\tf\tLgkv;\tt\tfield_1724\tplayer
+\tm\t()Lfzz;\tR\tmethod_1551\tgetInstance
+\tm\t(Lgkv;)V\ta\tmethod_1000\tsetPlayer
+\t\tp\t1\t\t\tplayer
+\tm\t(I)V\ta\tmethod_1001\tsetFps
+c\tgkv\tnet/minecraft/class_746\tnet/minecraft/client/network/ClientPlayerEntity
+c\tgkv$a\t\tnet/minecraft/client/network/ClientPlayerEntity$Inner
+";
+
+    const MAPPING: &str = r#"{
+        "classes": {
+            "net/minecraft/client/Minecraft": {
+                "name": "fzz",
+                "methods": {
+                    "getInstance": {"name": "R", "signature": "()Lfzz;"},
+                    "setPlayer": [
+                        {"name": "a", "signature": "(Lgkv;)V"},
+                        {"name": "a", "signature": "(I)V"}
+                    ],
+                    "toString": {"name": "toString", "signature": "()Ljava/lang/String;"}
+                },
+                "fields": {
+                    "player": {"name": "t", "signature": "Lgkv;"},
+                    "fps": {"name": "t", "signature": "I"}
+                }
+            },
+            "net/minecraft/client/player/LocalPlayer$Inner": {
+                "name": "gkv$a",
+                "methods": {},
+                "fields": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse() {
+        let tiny = TinyMapping::parse(TINY).unwrap();
+
+        assert_eq!(tiny.namespaces(), ["official", "intermediary", "named"]);
+        assert_eq!(tiny.classes.len(), 3);
+        assert_eq!(tiny.classes[0].methods.len(), 3);
+        assert_eq!(
+            tiny.map_class("fzz", Namespace::Official, Namespace::Intermediary),
+            Some("net/minecraft/class_310")
+        );
+        // Empty names fall back to the first namespace
+        assert_eq!(
+            tiny.map_class("gkv$a", Namespace::Official, Namespace::Intermediary),
+            Some("gkv$a")
+        );
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        assert!(TinyMapping::parse("v1\tofficial\tintermediary\n").is_err());
+        let error = TinyMapping::parse("tiny\t2\t0\tofficial\tintermediary\nc\ta\n").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn test_remap_namespace() {
+        let tiny = TinyMapping::parse(TINY).unwrap();
+        let mut mapping: Mapping = serde_json::from_str(MAPPING).unwrap();
+        mapping
+            .remap_namespace(&tiny, Namespace::Intermediary)
+            .unwrap();
+
+        assert_eq!(mapping.get_namespace(), Namespace::Intermediary);
+
+        let minecraft = mapping.get_class("net/minecraft/client/Minecraft");
+        assert_eq!(minecraft.name, "net/minecraft/class_310");
+        // Fields sharing an obfuscated name are told apart by descriptor too
+        assert_eq!(minecraft.get_field("player").name, "field_1724");
+        assert_eq!(minecraft.get_field("fps").name, "field_1699");
+        assert_eq!(
+            minecraft.get_field("player").signature.as_deref(),
+            Some("Lnet/minecraft/class_746;")
+        );
+
+        let get_instance = minecraft.get_method("getInstance");
+        assert_eq!(get_instance.name, "method_1551");
+        assert_eq!(get_instance.signature, "()Lnet/minecraft/class_310;");
+
+        // Overloads sharing an obfuscated name are told apart by descriptor
+        let overloads = minecraft.get_methods("setPlayer");
+        assert_eq!(overloads[0].name, "method_1000");
+        assert_eq!(overloads[0].signature, "(Lnet/minecraft/class_746;)V");
+        assert_eq!(overloads[1].name, "method_1001");
+
+        // Members unknown to the Tiny mapping keep their name
+        assert_eq!(minecraft.get_method("toString").name, "toString");
+        assert_eq!(
+            mapping
+                .get_class("net/minecraft/client/player/LocalPlayer$Inner")
+                .name,
+            "gkv$a"
+        );
+    }
+}
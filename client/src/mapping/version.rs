@@ -0,0 +1,58 @@
+use crate::client::DarkClient;
+use crate::mapping::class_loader::game_class_loader;
+use crate::mapping::error::MappingError;
+use jni::objects::{JByteArray, JValue};
+use jni::JNIEnv;
+use serde::Deserialize;
+
+/// Resource bundled in the client jar describing the game version
+const VERSION_RESOURCE: &str = "version.json";
+
+/// Entry point of the game, kept unobfuscated so the launcher can start it
+const MAIN_CLASS: &str = "net/minecraft/client/main/Main";
+
+#[derive(Debug, Deserialize)]
+struct VersionInfo {
+    id: String,
+}
+
+/// Reads the version of the running game, e.g. `1.21.5`
+///
+/// This reads the same `version.json` resource as `DetectedVersion.tryDetectVersion`,
+/// so it works before any mapping is loaded.
+pub fn detect_game_version() -> Result<String, Box<dyn std::error::Error>> {
+    let mut env = DarkClient::instance().get_env()?;
+
+    // A failed call leaves its exception pending, it must be cleared before the next JNI call
+    read_version(&mut env).map_err(|e| match MappingError::take_exception(&mut env) {
+        Some(exception) => exception.into(),
+        None => e,
+    })
+}
+
+fn read_version(env: &mut JNIEnv) -> Result<String, Box<dyn std::error::Error>> {
+    // Launchers and mod loaders keep the game jar out of the system class loader, the resource is read
+    // with the class loader of the game like the classes
+    let class_loader = game_class_loader(env, MAIN_CLASS)?;
+
+    let resource_name = env.new_string(VERSION_RESOURCE)?;
+    let stream = env
+        .call_method(
+            &class_loader,
+            "getResourceAsStream",
+            "(Ljava/lang/String;)Ljava/io/InputStream;",
+            &[JValue::Object(&resource_name)],
+        )?
+        .l()?;
+
+    if stream.is_null() {
+        return Err(format!("{} not found in the game classpath", VERSION_RESOURCE).into());
+    }
+
+    let bytes = env.call_method(&stream, "readAllBytes", "()[B", &[])?.l()?;
+    env.call_method(&stream, "close", "()V", &[])?;
+    let bytes = env.convert_byte_array(JByteArray::from(bytes))?;
+
+    let info: VersionInfo = serde_json::from_slice(&bytes)?;
+    Ok(info.id)
+}
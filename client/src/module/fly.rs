@@ -1,5 +1,6 @@
 use crate::mapping::entity::player::LocalPlayer;
 use crate::module::{FlyModule, KeyboardKey, Module, ModuleCategory, ModuleData};
+use log::error;
 
 impl FlyModule {
     pub fn new(player: LocalPlayer) -> Self {
@@ -19,12 +20,16 @@ impl FlyModule {
 impl Module for FlyModule {
     fn on_start(&self) {
         // Enables flying
-        self.module.player.abilities.fly(true);
+        if let Err(e) = self.module.player.abilities.fly(true) {
+            error!("Unable to enable flying: {}", e);
+        }
     }
 
     fn on_stop(&self) {
         // Disables flying
-        self.module.player.abilities.fly(false);
+        if let Err(e) = self.module.player.abilities.fly(false) {
+            error!("Unable to disable flying: {}", e);
+        }
     }
 
     fn on_tick(&self) {
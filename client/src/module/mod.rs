@@ -5,6 +5,7 @@ pub mod fly;
 
 pub type ModuleType = dyn Module + Send + Sync;
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ModuleCategory {
     COMBAT,
@@ -53,10 +53,9 @@ impl eframe::App for InjectorGUI {
 
             if ui.button("find").clicked() {
                 self.pid = platform::find_pid();
-                if self.pid.is_none() {
-                    self.status = "Failed to find PID".to_owned();
-                } else {
-                    self.status = format!("Found PID: {}", self.pid.unwrap());
+                match self.pid {
+                    Some(pid) => self.status = format!("Found PID: {}", pid),
+                    None => self.status = "Failed to find PID".to_owned(),
                 }
             }
 
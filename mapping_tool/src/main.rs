@@ -0,0 +1,305 @@
+use client::mapping::check::references;
+use client::mapping::error::{MappingError, StackFrame};
+use client::mapping::packed::is_packed;
+use client::mapping::{Mapping, MinecraftClassType};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage:
+    mapping_tool convert <client.txt> [mappings.json] [--version <id>] [--descriptors runtime|mojang]
+        Convert Mojang ProGuard mappings, descriptors use runtime class names unless mojang is given
+    mapping_tool pack <mappings.json> [mappings.bin]
+        Write a mapping in the packed binary format, loaded one class at a time by the client
+    mapping_tool bench <mappings.json> [--iterations <count>]
+        Compare the startup time and heap usage of the client with the JSON and packed formats
+    mapping_tool diff <old> <new> [--referenced]
+        List the classes and members added, removed or with another signature, from mapping files or
+        ProGuard mappings, only the ones used by the client with --referenced";
+
+/// Counts the bytes allocated on the heap, used by `bench` to measure the size of a loaded mapping
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("convert") => convert(&args[1..]),
+        Some("pack") => pack(&args[1..]),
+        Some("bench") => bench(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        _ => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Command line arguments split into positional arguments, `--name value` options and `--name` flags
+struct Arguments<'a> {
+    positional: Vec<&'a str>,
+    options: HashMap<&'a str, &'a str>,
+    flags: Vec<&'a str>,
+}
+
+impl<'a> Arguments<'a> {
+    fn parse(
+        args: &'a [String],
+        names: &[&str],
+        flag_names: &[&str],
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut flags = Vec::new();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if names.contains(&name) => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value for --{}", name))?;
+                    options.insert(name, value.as_str());
+                }
+                Some(name) if flag_names.contains(&name) => flags.push(name),
+                Some(_) => return Err(format!("unknown option {}\n{}", arg, USAGE).into()),
+                None => positional.push(arg.as_str()),
+            }
+        }
+
+        Ok(Self {
+            positional,
+            options,
+            flags,
+        })
+    }
+}
+
+fn convert(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Arguments::parse(args, &["version", "descriptors"], &[])?;
+    let input = *args.positional.first().ok_or(USAGE)?;
+    let output = args.positional.get(1).copied().unwrap_or("mappings.json");
+
+    let contents = fs::read_to_string(input).map_err(|e| format!("{}: {}", input, e))?;
+    let mut mapping = Mapping::from_proguard(&contents).map_err(|e| format!("{}: {}", input, e))?;
+    if let Some(version) = args.options.get("version") {
+        mapping.set_version(Some(version.to_string()));
+    }
+    match args.options.get("descriptors") {
+        None | Some(&"runtime") => {}
+        Some(&"mojang") => mapping.deobfuscate_descriptors(),
+        Some(other) => return Err(format!("unknown descriptor names {}\n{}", other, USAGE).into()),
+    }
+
+    // Going through a Value sorts the keys, so the output is stable between runs
+    let value = serde_json::to_value(&mapping)?;
+    fs::write(output, serde_json::to_string_pretty(&value)?)?;
+
+    println!("Converted {} to {}", input, output);
+    Ok(())
+}
+
+fn pack(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Arguments::parse(args, &[], &[])?;
+    let input = *args.positional.first().ok_or(USAGE)?;
+    let output = args.positional.get(1).copied().unwrap_or("mappings.bin");
+
+    let contents = fs::read(input).map_err(|e| format!("{}: {}", input, e))?;
+    let mapping = Mapping::from_json(&contents).map_err(|e| format!("{}: {}", input, e))?;
+    let packed = mapping
+        .to_packed()
+        .map_err(|e| format!("{}: {}", input, e))?;
+    fs::write(output, &packed)?;
+
+    println!(
+        "Packed {} ({}) to {} ({})",
+        input,
+        format_bytes(contents.len()),
+        output,
+        format_bytes(packed.len())
+    );
+    Ok(())
+}
+
+/// Result of the client startup sequence on a mapping
+struct Measure {
+    load: Duration,
+    /// Self-check of the referenced members and the deobfuscation of one Java exception
+    startup: Duration,
+    /// Heap bytes held by the mapping once the client is started
+    heap: usize,
+}
+
+fn bench(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Arguments::parse(args, &["iterations"], &[])?;
+    let input = *args.positional.first().ok_or(USAGE)?;
+    let iterations: u32 = match args.options.get("iterations") {
+        Some(iterations) => iterations
+            .parse()
+            .map_err(|_| format!("invalid iteration count {}", iterations))?,
+        None => 10,
+    };
+
+    let contents = fs::read(input).map_err(|e| format!("{}: {}", input, e))?;
+    // Borrowed like the embedded mapping, so the data itself isn't counted in the heap usage
+    let packed: &'static [u8] = Mapping::from_json(&contents)
+        .map_err(|e| format!("{}: {}", input, e))?
+        .to_packed()?
+        .leak();
+
+    let json = measure(iterations, || Mapping::from_json(&contents).unwrap());
+    let packed_measure = measure(iterations, || Mapping::from_packed(packed).unwrap());
+
+    println!(
+        "{:<8} {:>10} {:>12} {:>12} {:>10}",
+        "format", "size", "load", "startup", "heap"
+    );
+    for (name, size, measure) in [
+        ("json", contents.len(), json),
+        ("packed", packed.len(), packed_measure),
+    ] {
+        println!(
+            "{:<8} {:>10} {:>12.2?} {:>12.2?} {:>10}",
+            name,
+            format_bytes(size),
+            measure.load,
+            measure.startup,
+            format_bytes(measure.heap)
+        );
+    }
+    println!(
+        "Average of {} startups, each resolving the {} mapped references of the self-check without a \
+         JVM and deobfuscating one Java exception",
+        iterations,
+        references()
+            .filter(|reference| reference.class().is_mapped())
+            .count()
+    );
+    Ok(())
+}
+
+fn measure(iterations: u32, load: impl Fn() -> Mapping) -> Measure {
+    let mut result = Measure {
+        load: Duration::ZERO,
+        startup: Duration::ZERO,
+        heap: 0,
+    };
+
+    for _ in 0..iterations.max(1) {
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        let start = Instant::now();
+        let mapping = load();
+        result.load += start.elapsed();
+
+        let start = Instant::now();
+        mapping.resolve_references();
+        // What `java_error` does with an exception thrown in the game
+        let _ = mapping
+            .reverse()
+            .deobfuscate_error(sample_exception(&mapping));
+        result.startup += start.elapsed();
+        result.heap = ALLOCATED.load(Ordering::Relaxed).saturating_sub(before);
+    }
+
+    result.load /= iterations.max(1);
+    result.startup /= iterations.max(1);
+    result
+}
+
+/// Exception thrown in the main class of the game, with its runtime names
+fn sample_exception(mapping: &Mapping) -> MappingError {
+    let class = mapping
+        .try_get_class(MinecraftClassType::Minecraft.get_name())
+        .map(|class| class.get_name().replace('/', "."))
+        .unwrap_or_default();
+
+    MappingError::JavaException {
+        class: "java.lang.IllegalStateException".to_string(),
+        message: None,
+        stack_trace: vec![StackFrame {
+            class,
+            method: "tick".to_string(),
+            file: None,
+            line: -1,
+        }],
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+fn diff(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let args = Arguments::parse(args, &[], &["referenced"])?;
+    let [old, new] = args.positional[..] else {
+        return Err(USAGE.into());
+    };
+
+    let mut diff = read_mapping(old)?.diff(&read_mapping(new)?);
+    if args.flags.contains(&"referenced") {
+        diff = diff.referenced();
+    }
+
+    if diff.is_empty() {
+        println!("No changes between {} and {}", old, new);
+    } else {
+        println!("{}", diff);
+    }
+    Ok(())
+}
+
+/// Reads a mapping file, packed or JSON, or Mojang ProGuard mappings
+fn read_mapping(path: &str) -> Result<Mapping, Box<dyn Error>> {
+    let contents = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mapping = if is_packed(&contents) {
+        Mapping::from_packed(contents).map_err(|e| format!("{}: {}", path, e))?
+    } else if contents.trim_ascii_start().starts_with(b"{") {
+        Mapping::from_json(&contents).map_err(|e| format!("{}: {}", path, e))?
+    } else {
+        let contents = String::from_utf8(contents).map_err(|e| format!("{}: {}", path, e))?;
+        Mapping::from_proguard(&contents).map_err(|e| format!("{}: {}", path, e))?
+    };
+    Ok(mapping)
+}